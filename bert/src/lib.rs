@@ -40,6 +40,7 @@ mod config;
 mod model;
 mod pipeline;
 mod pooler;
+mod similarity;
 mod tokenizer;
 
 pub use crate::{
@@ -47,6 +48,7 @@ pub use crate::{
     pipeline::{Pipeline, PipelineError},
     pooler::{
         AveragePooler,
+        ClsPooler,
         Embedding,
         Embedding1,
         Embedding2,
@@ -54,6 +56,13 @@ pub use crate::{
         InvalidEmbedding,
         NonePooler,
         NormalizedEmbedding,
+        TokenEmbedding,
+    },
+    similarity::{
+        cosine_similarity,
+        cosine_similarity_one_to_many,
+        pairwise_cosine_similarity,
+        DimensionMismatch,
     },
 };
 
@@ -19,9 +19,10 @@ use thiserror::Error;
 
 use crate::{
     model::Model,
-    pooler::{Embedding1, Embedding2},
+    pooler::{Embedding1, Embedding2, TokenEmbedding},
     tokenizer::Tokenizer,
     AveragePooler,
+    ClsPooler,
     FirstPooler,
     NonePooler,
 };
@@ -56,6 +57,21 @@ impl Pipeline<NonePooler> {
 
         Ok(pooling)
     }
+
+    /// Computes the embeddings of the tokens of the sequence.
+    ///
+    /// Other than [`run()`](Self::run) the embeddings are labeled with their tokens and the
+    /// special and padding tokens are dropped.
+    pub fn run_tokens(
+        &self,
+        sequence: impl AsRef<str>,
+    ) -> Result<Vec<TokenEmbedding>, PipelineError> {
+        let encoding = self.tokenizer.encode(sequence)?;
+        let embedding = self.model.embed(&encoding)?;
+        let pooling = NonePooler::pool(&embedding.extract()?.view());
+
+        Ok(NonePooler::label(&pooling, &encoding))
+    }
 }
 
 impl Pipeline<FirstPooler> {
@@ -69,6 +85,17 @@ impl Pipeline<FirstPooler> {
     }
 }
 
+impl Pipeline<ClsPooler> {
+    /// Computes the pooled embedding of the sequence.
+    pub fn run(&self, sequence: impl AsRef<str>) -> Result<Embedding1, PipelineError> {
+        let encoding = self.tokenizer.encode(sequence)?;
+        let embedding = self.model.embed(&encoding)?;
+        let pooling = ClsPooler::pool(&embedding.extract()?.view(), &encoding);
+
+        Ok(pooling)
+    }
+}
+
 impl Pipeline<AveragePooler> {
     /// Computes the pooled embedding of the sequence.
     pub fn run(&self, sequence: impl AsRef<str>) -> Result<Embedding1, PipelineError> {
@@ -91,12 +118,15 @@ impl<P> Pipeline<P> {
 mod tests {
     use std::path::PathBuf;
 
-    use xayn_test_utils::asset::{e5_mocked, ort, smbert_mocked};
+    use xayn_test_utils::{
+        assert_approx_eq,
+        asset::{e5_mocked, ort, smbert_mocked},
+    };
 
     use super::*;
     use crate::{
         config::Config,
-        pooler::{AveragePooler, FirstPooler, NonePooler},
+        pooler::{AveragePooler, ClsPooler, FirstPooler, NonePooler},
     };
 
     fn pipeline<P>(dir: PathBuf) -> Pipeline<P> {
@@ -118,6 +148,23 @@ mod tests {
         assert_eq!(embeddings.shape(), [2, pipeline.embedding_size()]);
     }
 
+    #[test]
+    fn test_pipeline_none_tokens() {
+        let pipeline = pipeline::<NonePooler>(smbert_mocked().unwrap());
+
+        let embeddings = pipeline.run_tokens("This is a sequence.").unwrap();
+        // the 7 tokens of the unlabeled embeddings without [CLS] and [SEP]
+        assert_eq!(embeddings.len(), 5);
+        for embedding in &embeddings {
+            assert_eq!(embedding.embedding.shape(), [pipeline.embedding_size()]);
+        }
+        for window in embeddings.windows(2) {
+            assert!(window[0].offsets.1 <= window[1].offsets.0);
+        }
+
+        assert!(pipeline.run_tokens("").unwrap().is_empty());
+    }
+
     #[test]
     fn test_pipeline_first() {
         let pipeline = pipeline::<FirstPooler>(smbert_mocked().unwrap());
@@ -129,6 +176,19 @@ mod tests {
         assert_eq!(embeddings.shape(), [pipeline.embedding_size()]);
     }
 
+    #[test]
+    fn test_pipeline_cls() {
+        let pipeline = pipeline::<ClsPooler>(smbert_mocked().unwrap());
+
+        let embeddings = pipeline.run("This is a sequence.").unwrap();
+        assert_eq!(embeddings.shape(), [pipeline.embedding_size()]);
+        let first = pipeline::<FirstPooler>(smbert_mocked().unwrap());
+        assert_approx_eq!(f32, embeddings, first.run("This is a sequence.").unwrap());
+
+        let embeddings = pipeline.run("").unwrap();
+        assert_eq!(embeddings.shape(), [pipeline.embedding_size()]);
+    }
+
     #[test]
     fn test_pipeline_average() {
         let pipeline = pipeline::<AveragePooler>(smbert_mocked().unwrap());
@@ -80,6 +80,27 @@ pub struct NormalizedEmbedding(Embedding1);
 pub struct InvalidEmbedding;
 
 impl Embedding1 {
+    /// Creates an embedding from the values.
+    ///
+    /// Fails if the values are empty or contain any non-finite value.
+    pub fn try_from_slice(values: &[f32]) -> Result<Self, InvalidEmbedding> {
+        if values.is_empty() || !values.iter().all(|value| value.is_finite()) {
+            return Err(InvalidEmbedding);
+        }
+
+        Ok(Array1::from_vec(values.to_vec()).into())
+    }
+
+    /// The dimension of the embedding.
+    pub fn dim(&self) -> usize {
+        self.len()
+    }
+
+    /// Checks if the embedding is of the dimension.
+    pub fn has_dim(&self, dim: usize) -> bool {
+        self.dim() == dim
+    }
+
     pub fn normalize(mut self) -> Result<NormalizedEmbedding, InvalidEmbedding> {
         let norm = self.dot(&*self).sqrt();
         if !norm.is_finite() {
@@ -153,6 +174,9 @@ impl<'r> Decode<'r, Postgres> for Embedding1 {
 
 impl NormalizedEmbedding {
     /// The value is bounded in `[-1, 1]`.
+    ///
+    /// # Panics
+    /// Panics if the embeddings are of different dimensions.
     pub fn dot_product(&self, other: &Self) -> f32 {
         self.dot(&other.0 .0).clamp(-1., 1.)
     }
@@ -196,9 +220,24 @@ impl<'a> ApproxEqIter<'a, f32> for NormalizedEmbedding {
 /// The embedding is of shape `(token_size, embedding_size)`.
 pub type Embedding2 = Embedding<Ix2>;
 
+impl Embedding2 {
+    /// Gets the token embedding at the index.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    pub fn row(&self, index: usize) -> Option<Embedding1> {
+        (index < self.nrows()).then(|| self.0.row(index).to_owned().into())
+    }
+
+    /// Iterates over the token embeddings.
+    pub fn rows(&self) -> impl '_ + ExactSizeIterator<Item = Embedding1> {
+        self.0.rows().into_iter().map(|row| row.to_owned().into())
+    }
+}
+
 /// An inert pooling strategy.
 ///
-/// The embedding is just passed through.
+/// The embedding is just passed through. It is of shape `(token_size, embedding_size)`, where the
+/// tokens include the special and padding tokens of the encoding.
 pub struct NonePooler;
 
 impl NonePooler {
@@ -206,11 +245,45 @@ impl NonePooler {
     pub(crate) fn pool(embedding: &ArrayView<'_, f32, IxDyn>) -> Embedding2 {
         embedding.slice(s![0, .., ..]).to_owned().into()
     }
+
+    /// Labels the token embeddings with their tokens.
+    ///
+    /// Special and padding tokens are dropped.
+    pub(crate) fn label(embedding: &Embedding2, encoding: &Encoding) -> Vec<TokenEmbedding> {
+        encoding
+            .get_tokens()
+            .iter()
+            .zip(encoding.get_offsets())
+            .zip(encoding.get_special_tokens_mask())
+            .zip(encoding.get_attention_mask())
+            .zip(embedding.rows())
+            .filter_map(|((((token, &offsets), &special), &attention), embedding)| {
+                (special == 0 && attention != 0).then(|| TokenEmbedding {
+                    token: token.clone(),
+                    offsets,
+                    embedding,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The embedding of a single token of a sequence.
+#[derive(Clone, Debug)]
+pub struct TokenEmbedding {
+    /// The token.
+    pub token: String,
+    /// The byte offsets of the token in the sequence.
+    pub offsets: (usize, usize),
+    /// The embedding of shape `(embedding_size,)`.
+    pub embedding: Embedding1,
 }
 
 /// A first token pooling strategy.
 ///
-/// The embedding is pooled over its first token.
+/// The embedding is pooled over its first token, i.e. the token at index 0 whether it is padding
+/// or not. For Bert-like tokenizers with right padding this is the `[CLS]` token, see
+/// [`ClsPooler`] for a padding independent alternative.
 pub struct FirstPooler;
 
 impl FirstPooler {
@@ -220,6 +293,25 @@ impl FirstPooler {
     }
 }
 
+/// A classification token pooling strategy.
+///
+/// The embedding is pooled over the classification token (eg. `[CLS]` for Bert-like tokenizers),
+/// which is the first active token of the sequence. This is independent of the padding direction.
+pub struct ClsPooler;
+
+impl ClsPooler {
+    /// Pools the embedding over its classification token.
+    pub(crate) fn pool(embedding: &ArrayView<'_, f32, IxDyn>, encoding: &Encoding) -> Embedding1 {
+        let position = encoding
+            .get_attention_mask()
+            .iter()
+            .position(|&mask| mask == 1)
+            .unwrap_or_default();
+
+        embedding.slice(s![0, position, ..]).to_owned().into()
+    }
+}
+
 /// An average token pooling strategy.
 ///
 /// The embedding is pooled over its averaged tokens.
@@ -250,11 +342,22 @@ impl AveragePooler {
 mod tests {
     use std::{collections::HashMap, f32::consts::SQRT_2};
 
-    use ndarray::arr3;
+    use ndarray::{arr2, arr3};
     use xayn_test_utils::assert_approx_eq;
 
     use super::*;
 
+    #[test]
+    fn test_try_from_slice() {
+        assert!(Embedding1::try_from_slice(&[]).is_err());
+        assert!(Embedding1::try_from_slice(&[1., f32::NAN, 3.]).is_err());
+        assert!(Embedding1::try_from_slice(&[f32::INFINITY]).is_err());
+        assert!(Embedding1::try_from_slice(&[f32::NEG_INFINITY, 0.]).is_err());
+
+        let embedding = Embedding1::try_from_slice(&[0., 1., -2.]).unwrap();
+        assert_approx_eq!(f32, embedding, [0., 1., -2.]);
+    }
+
     #[test]
     fn test_normalize() {
         assert!(Embedding1::from([f32::NAN]).normalize().is_err());
@@ -280,6 +383,25 @@ mod tests {
         assert_approx_eq!(f32, embedding, [[1., 2., 3.], [4., 5., 6.]]);
     }
 
+    #[test]
+    fn test_rows() {
+        let embedding = Embedding2::from(arr2(&[
+            [1., 2., 3., 4.],
+            [5., 6., 7., 8.],
+            [9., 10., 11., 12.],
+        ]));
+
+        assert_approx_eq!(f32, embedding.row(0).unwrap(), [1., 2., 3., 4.]);
+        assert_approx_eq!(f32, embedding.row(2).unwrap(), [9., 10., 11., 12.]);
+        assert!(embedding.row(3).is_none());
+
+        let rows = embedding.rows().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 3);
+        for (i, row) in rows.into_iter().enumerate() {
+            assert_approx_eq!(f32, row, embedding.row(i).unwrap());
+        }
+    }
+
     #[test]
     fn test_first() {
         let embedding = arr3(&[[[1., 2., 3.], [4., 5., 6.]]]).into_dyn();
@@ -287,22 +409,40 @@ mod tests {
         assert_approx_eq!(f32, embedding, [1., 2., 3.]);
     }
 
+    fn attention_mask(mask: Vec<u32>) -> Encoding {
+        Encoding::new(
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            mask,
+            Vec::new(),
+            HashMap::new(),
+        )
+    }
+
+    #[test]
+    fn test_cls() {
+        let embedding = arr3(&[[[1., 2., 3.], [4., 5., 6.], [7., 8., 9.]]]).into_dyn();
+
+        let encoding = attention_mask(vec![1, 1, 0]);
+        let pooling = ClsPooler::pool(&embedding.view(), &encoding);
+        assert_approx_eq!(f32, pooling, [1., 2., 3.]);
+
+        let encoding = attention_mask(vec![0, 1, 1]);
+        let pooling = ClsPooler::pool(&embedding.view(), &encoding);
+        assert_approx_eq!(f32, pooling, [4., 5., 6.]);
+
+        let encoding = attention_mask(vec![0, 0, 0]);
+        let pooling = ClsPooler::pool(&embedding.view(), &encoding);
+        assert_approx_eq!(f32, pooling, [1., 2., 3.]);
+    }
+
     #[test]
     fn test_average() {
         let embedding = arr3(&[[[1., 2., 3.], [4., 5., 6.]]]).into_dyn();
-        let attention_mask = |mask| {
-            Encoding::new(
-                Vec::new(),
-                Vec::new(),
-                Vec::new(),
-                Vec::new(),
-                Vec::new(),
-                Vec::new(),
-                mask,
-                Vec::new(),
-                HashMap::new(),
-            )
-        };
 
         let encoding = attention_mask(vec![0, 0]);
         let pooling = AveragePooler::pool(&embedding.view(), &encoding);
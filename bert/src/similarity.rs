@@ -0,0 +1,228 @@
+// Copyright 2023 Xayn AG
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use displaydoc::Display;
+use ndarray::Array2;
+use thiserror::Error;
+
+use crate::pooler::Embedding1;
+
+/// Embeddings of different dimensions {left} and {right} can't be compared.
+#[derive(Clone, Copy, Debug, Display, Error, PartialEq)]
+pub struct DimensionMismatch {
+    pub left: usize,
+    pub right: usize,
+}
+
+/// Computes the cosine similarity of two embeddings.
+///
+/// The value is bounded in `[-1, 1]` and is `0` if any of the two embeddings is zero. Fails if
+/// the embeddings are of different dimensions.
+pub fn cosine_similarity(left: &Embedding1, right: &Embedding1) -> Result<f32, DimensionMismatch> {
+    if !left.has_dim(right.dim()) {
+        return Err(DimensionMismatch {
+            left: left.dim(),
+            right: right.dim(),
+        });
+    }
+
+    let norm = left.dot(&**left).sqrt() * right.dot(&**right).sqrt();
+
+    Ok(cosine(left.dot(&**right), norm))
+}
+
+/// Computes the cosine similarities of the query to each of the documents.
+///
+/// The values are the same as from calling [`cosine_similarity()`] for each document, but the norm
+/// of the query is computed only once. Fails if any document is of a different dimension.
+pub fn cosine_similarity_one_to_many<'a>(
+    query: &Embedding1,
+    documents: impl IntoIterator<Item = &'a Embedding1>,
+) -> Result<Vec<f32>, DimensionMismatch> {
+    let query_norm = query.dot(&**query).sqrt();
+
+    documents
+        .into_iter()
+        .map(|document| {
+            if !document.has_dim(query.dim()) {
+                return Err(DimensionMismatch {
+                    left: query.dim(),
+                    right: document.dim(),
+                });
+            }
+
+            let norm = query_norm * document.dot(&**document).sqrt();
+
+            Ok(cosine(query.dot(&**document), norm))
+        })
+        .collect()
+}
+
+/// Computes the pairwise cosine similarities of the embeddings.
+///
+/// The result is a symmetric matrix of shape `(n, n)` with a diagonal of exactly `1`. The
+/// off-diagonal values are bounded in `[-1, 1]` and are `0` if any of the two embeddings is zero.
+/// Fails if the embeddings are of different dimensions.
+pub fn pairwise_cosine_similarity<'a>(
+    embeddings: impl IntoIterator<Item = &'a Embedding1>,
+) -> Result<Array2<f32>, DimensionMismatch> {
+    let embeddings = embeddings.into_iter().collect::<Vec<_>>();
+    if let Some(first) = embeddings.first() {
+        if let Some(other) = embeddings.iter().find(|other| !other.has_dim(first.dim())) {
+            return Err(DimensionMismatch {
+                left: first.dim(),
+                right: other.dim(),
+            });
+        }
+    }
+
+    let norms = embeddings
+        .iter()
+        .map(|&embedding| embedding.dot(&**embedding).sqrt())
+        .collect::<Vec<_>>();
+
+    let size = embeddings.len();
+    let mut similarities = Array2::zeros((size, size));
+    for i in 0..size {
+        similarities[[i, i]] = 1.;
+        for j in i + 1..size {
+            let similarity = cosine(embeddings[i].dot(&**embeddings[j]), norms[i] * norms[j]);
+            similarities[[i, j]] = similarity;
+            similarities[[j, i]] = similarity;
+        }
+    }
+
+    Ok(similarities)
+}
+
+/// Computes the cosine similarity from the dot product and the product of the norms.
+fn cosine(dot: f32, norm: f32) -> f32 {
+    if norm > 0. {
+        (dot / norm).clamp(-1., 1.)
+    } else {
+        0.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    use xayn_test_utils::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_pairwise_cosine_similarity() {
+        let embeddings = [
+            Embedding1::from([1., 0.]),
+            Embedding1::from([2., 2.]),
+            Embedding1::from([0., -3.]),
+        ];
+        let similarities = pairwise_cosine_similarity(&embeddings).unwrap();
+
+        assert_eq!(similarities.shape(), [3, 3]);
+        for i in 0..3 {
+            assert_approx_eq!(f32, similarities[[i, i]], 1., ulps = 0);
+            for j in 0..3 {
+                assert_approx_eq!(f32, similarities[[i, j]], similarities[[j, i]]);
+            }
+        }
+        assert_approx_eq!(f32, similarities[[0, 1]], FRAC_1_SQRT_2, epsilon = 1e-6);
+        assert_approx_eq!(f32, similarities[[0, 2]], 0., epsilon = 1e-6);
+        assert_approx_eq!(f32, similarities[[1, 2]], -FRAC_1_SQRT_2, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_pairwise_cosine_similarity_zero() {
+        let embeddings = [Embedding1::from([0., 0.]), Embedding1::from([1., 1.])];
+        let similarities = pairwise_cosine_similarity(&embeddings).unwrap();
+
+        assert_approx_eq!(f32, similarities, [[1., 0.], [0., 1.]]);
+    }
+
+    #[test]
+    fn test_pairwise_cosine_similarity_mismatched_dim() {
+        let embeddings = [
+            Embedding1::from([1., 0.]),
+            Embedding1::from([1., 0.]),
+            Embedding1::from([1., 0., 0.]),
+        ];
+
+        assert_eq!(
+            pairwise_cosine_similarity(&embeddings).unwrap_err(),
+            DimensionMismatch { left: 2, right: 3 },
+        );
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        let left = Embedding1::from([1., 0.]);
+        assert!(left.has_dim(2));
+        assert_eq!(left.dim(), 2);
+
+        assert_approx_eq!(
+            f32,
+            cosine_similarity(&left, &Embedding1::from([2., 2.])).unwrap(),
+            FRAC_1_SQRT_2,
+            epsilon = 1e-6,
+        );
+        assert_approx_eq!(
+            f32,
+            cosine_similarity(&left, &Embedding1::from([0., 0.])).unwrap(),
+            0.,
+        );
+    }
+
+    #[test]
+    fn test_cosine_similarity_one_to_many() {
+        let query = Embedding1::from([1., 2.]);
+        let documents = [
+            Embedding1::from([1., 0.]),
+            Embedding1::from([-2., 1.]),
+            Embedding1::from([0., 0.]),
+            Embedding1::from([3., 6.]),
+        ];
+        let similarities = cosine_similarity_one_to_many(&query, &documents).unwrap();
+
+        assert_eq!(similarities.len(), documents.len());
+        for (similarity, document) in similarities.into_iter().zip(&documents) {
+            assert_approx_eq!(
+                f32,
+                similarity,
+                cosine_similarity(&query, document).unwrap(),
+                ulps = 0,
+            );
+        }
+        assert!(cosine_similarity_one_to_many(&query, [])
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            cosine_similarity_one_to_many(&query, &[Embedding1::from([1., 0., 0.])]).unwrap_err(),
+            DimensionMismatch { left: 2, right: 3 },
+        );
+    }
+
+    #[test]
+    fn test_cosine_similarity_mismatched_dim() {
+        let left = Embedding1::from([1., 0.]);
+        let right = Embedding1::from([1., 0., 0.]);
+        assert!(!right.has_dim(2));
+
+        assert_eq!(
+            cosine_similarity(&left, &right).unwrap_err(),
+            DimensionMismatch { left: 2, right: 3 },
+        );
+    }
+}
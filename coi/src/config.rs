@@ -32,6 +32,7 @@ pub struct Config {
     shift_factor: f32,
     threshold: f32,
     min_cois: usize,
+    max_cois: Option<usize>,
     #[serde(with = "serde_duration_as_days")]
     horizon: Duration,
 }
@@ -45,6 +46,7 @@ impl Default for Config {
             shift_factor: 0.1,
             threshold: 0.67,
             min_cois: 1,
+            max_cois: None,
             horizon: Duration::from_secs(30 * SECONDS_PER_DAY),
         }
     }
@@ -59,6 +61,8 @@ pub enum Error {
     Threshold,
     /// Invalid minimum number of cois, expected positive value
     MinCois,
+    /// Invalid maximum number of cois, expected value not less than the minimum number of cois
+    MaxCois,
 }
 
 impl Config {
@@ -72,6 +76,12 @@ impl Config {
         if self.min_cois == 0 {
             return Err(Error::MinCois);
         }
+        if self
+            .max_cois
+            .is_some_and(|max_cois| max_cois < self.min_cois)
+        {
+            return Err(Error::MaxCois);
+        }
 
         Ok(())
     }
@@ -124,6 +134,22 @@ impl Config {
         Ok(self)
     }
 
+    /// The maximum number of cois, unbounded if `None`.
+    pub fn max_cois(&self) -> Option<usize> {
+        self.max_cois
+    }
+
+    /// Sets the maximum number of cois.
+    ///
+    /// # Errors
+    /// Fails if the maximum number is less than the minimum number.
+    pub fn with_max_cois(mut self, max_cois: Option<usize>) -> Result<Self, Error> {
+        self.max_cois = max_cois;
+        self.validate()?;
+
+        Ok(self)
+    }
+
     /// The time since the last view after which a coi becomes irrelevant.
     pub fn horizon(&self) -> Duration {
         self.horizon
@@ -43,10 +43,11 @@ pub use crate::{
     document::Document,
     point::{Coi, Id as CoiId},
     stats::{
+        coi_decay_factor,
         compute_coi_decay_factor,
         compute_coi_relevances,
         compute_coi_weights,
         Stats as CoiStats,
     },
-    system::System as CoiSystem,
+    system::{DocumentScoreExplanation, System as CoiSystem},
 };
@@ -31,6 +31,16 @@ impl Id {
     pub fn new() -> Self {
         Self(Uuid::new_v4())
     }
+
+    /// Creates an id from an existing uuid, e.g. of an externally persisted coi.
+    pub fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    /// Gets the uuid of the id.
+    pub fn as_uuid(&self) -> Uuid {
+        self.0
+    }
 }
 
 /// A center of interest.
@@ -105,6 +115,21 @@ pub(crate) mod tests {
             .collect()
     }
 
+    #[test]
+    fn test_id_uuid_roundtrip() {
+        let uuid = mock_uuid(42);
+        let id = Id::from_uuid(uuid);
+        assert_eq!(id.as_uuid(), uuid);
+        assert_eq!(Id::from_uuid(id.as_uuid()), id);
+
+        let cois = create_cois([[1., 0.], [0., 1.]], Utc::now());
+        let coi = cois
+            .iter()
+            .find(|coi| coi.id == Id::from_uuid(mock_uuid(1)))
+            .unwrap();
+        assert_eq!(coi.id.as_uuid(), mock_uuid(1));
+    }
+
     #[test]
     fn test_shift_coi_point_towards_other() {
         let mut cois = create_cois([[1., 1., 1.]], Utc::now());
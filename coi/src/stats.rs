@@ -103,25 +103,36 @@ pub fn compute_coi_relevances<'a>(
 /// Computes the time decay factor for a [`Coi`].
 ///
 /// The decay factor is based on its `last_view` stat relative to the current `time` and ranges in
-/// the interval `[0., 1.]`.
+/// the interval `[0., 1.]`. See [`coi_decay_factor()`] for details.
 pub fn compute_coi_decay_factor(
     horizon: Duration,
     time: DateTime<Utc>,
     last_view: DateTime<Utc>,
 ) -> f32 {
+    let age = time
+        .signed_duration_since(last_view)
+        .to_std()
+        .unwrap_or_default();
+
+    coi_decay_factor(age, horizon)
+}
+
+/// Computes the time decay factor for the `age` of a [`Coi`] since its last view.
+///
+/// The factor is `max(0, (exp(-0.1 * horizon) - exp(-0.1 * age)) / (exp(-0.1 * horizon) - 1))`,
+/// where `age` and `horizon` are measured in days. It ranges in the interval `[0., 1.]`, starting
+/// at `1` for a zero `age` and decaying exponentially to `0` once the `age` reaches the `horizon`.
+/// A zero `horizon` always yields `0`.
+pub fn coi_decay_factor(age: Duration, horizon: Duration) -> f32 {
     if horizon == Duration::ZERO {
         return 0.;
     }
 
-    let Ok(days) = time.signed_duration_since(last_view).to_std() else {
-        return 1.;
-    };
-
     const DAYS_SCALE: f32 = -0.1 / (60. * 60. * 24.);
     let horizon = (DAYS_SCALE * horizon.as_secs_f32()).exp();
-    let days = (DAYS_SCALE * days.as_secs_f32()).exp();
+    let age = (DAYS_SCALE * age.as_secs_f32()).exp();
 
-    ((horizon - days) / (horizon - 1.)).max(0.)
+    ((horizon - age) / (horizon - 1.)).max(0.)
 }
 
 /// Computes a weight distributions across [`Coi`]s based on their relevance.
@@ -238,4 +249,17 @@ mod tests {
         let factor = compute_coi_decay_factor(Duration::ZERO, now, now);
         assert_approx_eq!(f32, factor, 0.);
     }
+
+    #[test]
+    fn test_coi_decay_factor() {
+        let day = Duration::from_secs(SECONDS_PER_DAY);
+        let horizon = 30 * day;
+
+        assert_approx_eq!(f32, coi_decay_factor(Duration::ZERO, horizon), 1.);
+        assert_approx_eq!(f32, coi_decay_factor(5 * day, horizon), 0.585_914_55);
+        assert_approx_eq!(f32, coi_decay_factor(15 * day, horizon), 0.182_425_52);
+        assert_approx_eq!(f32, coi_decay_factor(horizon, horizon), 0.);
+        assert_approx_eq!(f32, coi_decay_factor(2 * horizon, horizon), 0.);
+        assert_approx_eq!(f32, coi_decay_factor(day, Duration::ZERO), 0.);
+    }
 }
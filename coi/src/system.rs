@@ -67,9 +67,33 @@ impl System {
 
         // If the embedding is too dissimilar, we create a new CoI instead
         cois.push(Coi::new(Id::new(), embedding.clone(), time));
+        self.evict_least_relevant_cois(cois, time);
         &cois[cois.len() - 1]
     }
 
+    /// Removes the least relevant [`Coi`]s if there are more than the maximum number of cois.
+    ///
+    /// The last coi is never removed.
+    fn evict_least_relevant_cois(&self, cois: &mut Vec<Coi>, time: DateTime<Utc>) {
+        let Some(max_cois) = self.config.max_cois() else {
+            return;
+        };
+
+        while cois.len() > max_cois {
+            let relevances =
+                compute_coi_relevances(&cois[..cois.len() - 1], self.config.horizon(), time);
+            let Some(index) = relevances
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(index, _)| index)
+            else {
+                break;
+            };
+            cois.remove(index);
+        }
+    }
+
     /// Computes the scores for all [`Document`]s wrt the [`Coi`]s.
     ///
     /// Each score ranges in the interval `[0., 1.]` if a [`Coi`] exists. The [coi weighting]
@@ -80,22 +104,82 @@ impl System {
     where
         D: Document,
     {
+        self.score_explained(documents, cois, time)
+            .map(|explanations| {
+                explanations
+                    .iter()
+                    .map(DocumentScoreExplanation::score)
+                    .collect()
+            })
+    }
+
+    /// Computes the explanations of the scores for all [`Document`]s wrt the [`Coi`]s.
+    ///
+    /// See [`score()`](Self::score) for details.
+    pub fn score_explained<D>(
+        &self,
+        documents: &[D],
+        cois: &[Coi],
+        time: DateTime<Utc>,
+    ) -> Option<Vec<DocumentScoreExplanation>>
+    where
+        D: Document,
+    {
+        let horizon = self.config.horizon();
+        let relevances = compute_coi_relevances(cois, horizon, time);
+
         documents
             .iter()
             .map(|document| {
                 find_closest_coi_index(cois, document.embedding()).map(|(index, similarity)| {
-                    let horizon = self.config.horizon();
-                    let decay =
-                        compute_coi_decay_factor(horizon, time, cois[index].stats.last_view);
-                    let relevance = compute_coi_relevances(cois, horizon, time)[index];
-
-                    (similarity * decay + relevance + 1.) / 4.
+                    let coi = &cois[index];
+                    DocumentScoreExplanation {
+                        coi_id: coi.id,
+                        similarity,
+                        decay: compute_coi_decay_factor(horizon, time, coi.stats.last_view),
+                        relevance: relevances[index],
+                    }
                 })
             })
             .collect()
     }
 }
 
+/// The explanation of the score of a [`Document`].
+///
+/// Only the [`Coi`] closest to the document contributes to its score.
+#[derive(Clone, Copy, Debug)]
+pub struct DocumentScoreExplanation {
+    /// The id of the closest coi.
+    pub coi_id: Id,
+    /// The similarity between the document and the coi.
+    pub similarity: f32,
+    /// The time decay factor of the coi.
+    pub decay: f32,
+    /// The relevance of the coi wrt all cois.
+    pub relevance: f32,
+}
+
+impl DocumentScoreExplanation {
+    /// The constant part of the score.
+    pub const BASELINE: f32 = 0.25;
+
+    /// The part of the score contributed by the decayed similarity.
+    pub fn similarity_contribution(&self) -> f32 {
+        self.similarity * self.decay / 4.
+    }
+
+    /// The part of the score contributed by the relevance.
+    pub fn relevance_contribution(&self) -> f32 {
+        self.relevance / 4.
+    }
+
+    /// The score of the document.
+    pub fn score(&self) -> f32 {
+        (self.similarity * self.decay + self.relevance + 1.) / 4.
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use xayn_test_utils::assert_approx_eq;
@@ -140,6 +224,25 @@ mod tests {
         assert_approx_eq!(f32, cois[1].point, [1., 0.]);
     }
 
+    #[test]
+    fn test_log_user_reaction_evicts_least_relevant_coi() {
+        let now = Utc::now();
+        let mut cois = create_cois([[1., 0., 0.], [0., 1., 0.]], now);
+        cois[0].stats.view_count = 5;
+        cois[0].stats.view_time = Duration::from_secs(60);
+        let relevant = cois[0].id;
+        let system = Config::default().with_max_cois(Some(2)).unwrap().build();
+
+        let id = system
+            .log_user_reaction(&mut cois, &[0., 0., 1.].try_into().unwrap(), now)
+            .id;
+
+        assert_eq!(cois.len(), 2);
+        assert_eq!(cois[0].id, relevant);
+        assert_eq!(cois[1].id, id);
+        assert_approx_eq!(f32, cois[1].point, [0., 0., 1.]);
+    }
+
     #[test]
     fn test_log_document_view_time() {
         let mut cois = create_cois([[1., 2., 3.]], Utc::now());
@@ -180,6 +283,34 @@ mod tests {
         assert!(scores[0] < scores[1]);
     }
 
+    #[test]
+    fn test_score_explained() {
+        let documents = vec![
+            TestDocument::new(0, [1., 0.1, 0.].try_into().unwrap()),
+            TestDocument::new(1, [0., 1., 0.2].try_into().unwrap()),
+        ];
+        let now = Utc::now();
+        let cois = create_cois([[1., 0., 0.], [0., 1., 0.]], now);
+        let system = Config::default().build();
+
+        let explanations = system.score_explained(&documents, &cois, now).unwrap();
+        let scores = system.score(&documents, &cois, now).unwrap();
+
+        assert_eq!(explanations.len(), 2);
+        assert_eq!(explanations[0].coi_id, cois[0].id);
+        assert_eq!(explanations[1].coi_id, cois[1].id);
+        for (explanation, score) in explanations.iter().zip(scores) {
+            assert_approx_eq!(
+                f32,
+                explanation.similarity_contribution()
+                    + explanation.relevance_contribution()
+                    + DocumentScoreExplanation::BASELINE,
+                score,
+                epsilon = 1e-6,
+            );
+        }
+    }
+
     #[test]
     fn test_score_no_cois() {
         let documents = vec![
@@ -221,10 +221,14 @@ pub async fn send_assert(
 
     let status = response.status();
     if status != expected {
+        let request_id = headers
+            .get("x-request-id")
+            .map(|id| String::from_utf8_lossy(id.as_bytes()).into_owned())
+            .unwrap_or_default();
         let bytes = response.bytes().await.unwrap();
         let text = String::from_utf8_lossy(&bytes);
         panic!(
-            "Failed to {method} {target}, status `{status}` instead of `{expected}`.\nBody: `{text}`\n"
+            "Failed to {method} {target}, status `{status}` instead of `{expected}`.\nRequest Id: `{request_id}`\nBody: `{text}`\n"
         );
     }
     response
@@ -825,6 +829,7 @@ async fn setup_web_dev_services(
         is_legacy_tenant: enable_legacy_tenant,
         es_index_name: None,
         model: None,
+        ingestion_enabled: None,
     }
     .into();
 
@@ -870,9 +875,9 @@ pub fn db_configs_for_testing(test_id: &TestId) -> (postgres::Config, elastic::C
         es_config = elastic::Config {
             url: "http://elasticsearch:9200".into(),
             index_name: es_index_name,
-            timeout: es_timeout,
             ..Default::default()
-        };
+        }
+        .with_timeout(es_timeout);
     } else {
         pg_config = postgres::Config {
             db: pg_db,
@@ -883,9 +888,9 @@ pub fn db_configs_for_testing(test_id: &TestId) -> (postgres::Config, elastic::C
         es_config = elastic::Config {
             url: "http://localhost:3092".into(),
             index_name: es_index_name,
-            timeout: es_timeout,
             ..Default::default()
         }
+        .with_timeout(es_timeout)
     }
     (pg_config, es_config)
 }
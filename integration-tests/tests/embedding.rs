@@ -0,0 +1,196 @@
+// Copyright 2023 Xayn AG
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use reqwest::{header::RETRY_AFTER, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use toml::toml;
+use xayn_integration_tests::{
+    send_assert,
+    send_assert_json,
+    test_app,
+    TEST_EMBEDDING_SIZE,
+    UNCHANGED_CONFIG,
+};
+use xayn_web_api::WebApi;
+
+#[derive(Deserialize)]
+struct TextEmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+#[test]
+fn test_text_embedding() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |client, url, _| async move {
+        let TextEmbeddingResponse { embedding } = send_assert_json(
+            &client,
+            client
+                .post(url.join("/embedding")?)
+                .json(&json!({ "text": "this is one sentence which we have" }))
+                .build()?,
+            StatusCode::OK,
+            false,
+        )
+        .await;
+        assert_eq!(embedding.len(), TEST_EMBEDDING_SIZE);
+
+        send_assert(
+            &client,
+            client
+                .post(url.join("/embedding")?)
+                .json(&json!({ "text": "" }))
+                .build()?,
+            StatusCode::BAD_REQUEST,
+            false,
+        )
+        .await;
+        Ok(())
+    });
+}
+
+#[test]
+fn test_text_embedding_too_large() {
+    test_app::<WebApi, _>(
+        Some(toml! {
+            [text_embedding]
+            max_text_size = 10
+        }),
+        |client, url, _| async move {
+            send_assert(
+                &client,
+                client
+                    .post(url.join("/embedding")?)
+                    .json(&json!({ "text": "this is one sentence which we have" }))
+                    .build()?,
+                StatusCode::PAYLOAD_TOO_LARGE,
+                false,
+            )
+            .await;
+            Ok(())
+        },
+    );
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    kind: String,
+    details: Value,
+}
+
+#[test]
+fn test_text_embedding_upstream_unavailable() {
+    const TENANT_ID: &str = "test_text_embedding_upstream_unavailable";
+    test_app::<WebApi, _>(
+        Some(toml! {
+            [models.unreachable]
+            type = "open_ai"
+            url = "http://127.0.0.1:1/"
+            api_key = "no-key"
+            embedding_size = 384
+        }),
+        |client, url, _| async move {
+            send_assert(
+                &client,
+                client
+                    .post(url.join("/_ops/silo_management")?)
+                    .json(&json!({
+                        "operations": [
+                            { "CreateTenant": { "tenant_id": TENANT_ID, "model": "unreachable" } },
+                        ]
+                    }))
+                    .build()?,
+                StatusCode::OK,
+                false,
+            )
+            .await;
+
+            let response = client
+                .post(url.join("/embedding")?)
+                .header("X-Xayn-Tenant-Id", TENANT_ID)
+                .json(&json!({ "text": "this is one sentence which we have" }))
+                .send()
+                .await?;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(response.headers()[RETRY_AFTER], "5");
+            let ErrorResponse { kind, details } = response.json().await?;
+            assert_eq!(kind, "UpstreamUnavailable");
+            assert_eq!(details, json!({ "service": "openai", "transient": true }));
+
+            send_assert(
+                &client,
+                client
+                    .post(url.join("/_ops/silo_management")?)
+                    .json(&json!({
+                        "operations": [
+                            { "DeleteTenant": { "tenant_id": TENANT_ID } },
+                        ]
+                    }))
+                    .build()?,
+                StatusCode::OK,
+                false,
+            )
+            .await;
+            Ok(())
+        },
+    );
+}
+
+#[derive(Deserialize)]
+struct ReadyResponse {
+    status: String,
+    unavailable_models: Vec<String>,
+}
+
+#[test]
+fn test_degraded_mode_if_model_is_missing() {
+    test_app::<WebApi, _>(
+        Some(toml! {
+            [embedding]
+            directory = "/this/model/does/not/exist"
+        }),
+        |client, url, _| async move {
+            let ReadyResponse {
+                status,
+                unavailable_models,
+            } = send_assert_json(
+                &client,
+                client.get(url.join("/ready")?).build()?,
+                StatusCode::OK,
+                false,
+            )
+            .await;
+            assert_eq!(status, "degraded");
+            assert_eq!(unavailable_models, ["default"]);
+
+            let response = client
+                .post(url.join("/documents")?)
+                .json(&json!({ "documents": [ { "id": "d1", "snippet": "snippet" } ] }))
+                .send()
+                .await?;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            let ErrorResponse { kind, details } = response.json().await?;
+            assert_eq!(kind, "EmbedderUnavailable");
+            assert_eq!(details, json!({ "model": "default" }));
+
+            send_assert(
+                &client,
+                client.get(url.join("/documents/_candidates")?).build()?,
+                StatusCode::OK,
+                false,
+            )
+            .await;
+            Ok(())
+        },
+    );
+}
@@ -15,7 +15,12 @@
 use std::collections::{HashMap, HashSet};
 
 use base64::{engine::general_purpose, Engine as _};
-use reqwest::{Client, StatusCode, Url};
+use reqwest::{
+    header::{ETAG, IF_NONE_MATCH},
+    Client,
+    StatusCode,
+    Url,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use toml::toml;
@@ -53,6 +58,8 @@ enum Kind {
     DocumentNotFound,
     FailedToValidateDocuments,
     FailedToDeleteSomeDocuments,
+    IngestionPaused,
+    PropertiesSchemaViolation,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Serialize)]
@@ -61,6 +68,8 @@ enum Details {
     Ingest(Vec<Value>),
     #[serde(rename = "errors")]
     Delete(Value),
+    #[serde(rename = "violations")]
+    Violations(Vec<String>),
 }
 
 #[derive(Deserialize)]
@@ -196,6 +205,40 @@ fn test_ingestion_bad_request() {
     });
 }
 
+#[test]
+fn test_ingestion_request_body_too_large() {
+    test_app::<WebApi, _>(
+        Some(toml! {
+            [net]
+            max_request_body_bytes = 1024
+        }),
+        |client, url, _| async move {
+            let snippet = vec!["a"; 2048].join("");
+            send_assert(
+                &client,
+                client
+                    .post(url.join("/documents")?)
+                    .json(&json!({ "documents": [ { "id": "d1", "snippet": snippet } ] }))
+                    .build()?,
+                StatusCode::PAYLOAD_TOO_LARGE,
+                false,
+            )
+            .await;
+            send_assert(
+                &client,
+                client
+                    .post(url.join("/documents")?)
+                    .json(&json!({ "documents": [ { "id": "d1", "snippet": "snippet" } ] }))
+                    .build()?,
+                StatusCode::CREATED,
+                false,
+            )
+            .await;
+            Ok(())
+        },
+    );
+}
+
 #[test]
 fn test_ingestion_created_with_file() {
     let txt_content = "once in a spring there was a fall";
@@ -694,3 +737,499 @@ fn test_ingestion_validation() {
         },
     );
 }
+
+#[derive(Debug, Deserialize)]
+struct PropertiesSchemaResponse {
+    schema: Option<Value>,
+}
+
+#[test]
+fn test_ingestion_properties_schema() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |client, url, _| async move {
+        let schema = json!({
+            "properties": { "category": { "type": "string" } },
+            "required": ["category"],
+            "additionalProperties": false
+        });
+        send_assert(
+            &client,
+            client
+                .put(url.join("/documents/_properties_schema")?)
+                .json(&json!({ "schema": schema }))
+                .build()?,
+            StatusCode::NO_CONTENT,
+            false,
+        )
+        .await;
+        let response = send_assert_json::<PropertiesSchemaResponse>(
+            &client,
+            client
+                .get(url.join("/documents/_properties_schema")?)
+                .build()?,
+            StatusCode::OK,
+            false,
+        )
+        .await;
+        assert_eq!(response.schema, Some(schema));
+
+        let error = send_assert_json::<Error>(
+            &client,
+            client
+                .post(url.join("/documents")?)
+                .json(&json!({
+                    "documents": [
+                        { "id": "d1", "snippet": "abc", "properties": { "category": "news" } },
+                        { "id": "d2", "snippet": "abc", "properties": { "categroy": "news" } },
+                    ]
+                }))
+                .build()?,
+            StatusCode::BAD_REQUEST,
+            false,
+        )
+        .await;
+        assert_eq!(error.kind, Kind::FailedToValidateDocuments);
+        assert_eq!(
+            error.details.unwrap(),
+            Details::Ingest(vec![json!({
+                "id": "d2",
+                "kind": "PropertiesSchemaViolation",
+                "details": {
+                    "violations": [
+                        "missing required property category",
+                        "property categroy is not allowed",
+                    ]
+                }
+            })]),
+        );
+
+        send_assert(
+            &client,
+            client
+                .put(url.join("/documents/d1/properties/category")?)
+                .json(&json!({ "property": 1 }))
+                .build()?,
+            StatusCode::BAD_REQUEST,
+            false,
+        )
+        .await;
+        let error = send_assert_json::<Error>(
+            &client,
+            client
+                .delete(url.join("/documents/d1/properties/category")?)
+                .build()?,
+            StatusCode::BAD_REQUEST,
+            false,
+        )
+        .await;
+        assert_eq!(error.kind, Kind::PropertiesSchemaViolation);
+        assert_eq!(
+            error.details.unwrap(),
+            Details::Violations(vec!["missing required property category".into()]),
+        );
+        let error = send_assert_json::<Error>(
+            &client,
+            client
+                .delete(url.join("/documents/d1/properties")?)
+                .build()?,
+            StatusCode::BAD_REQUEST,
+            false,
+        )
+        .await;
+        assert_eq!(error.kind, Kind::PropertiesSchemaViolation);
+        assert_eq!(
+            error.details.unwrap(),
+            Details::Violations(vec!["missing required property category".into()]),
+        );
+
+        send_assert(
+            &client,
+            client
+                .delete(url.join("/documents/_properties_schema")?)
+                .build()?,
+            StatusCode::NO_CONTENT,
+            false,
+        )
+        .await;
+        let response = send_assert_json::<PropertiesSchemaResponse>(
+            &client,
+            client
+                .get(url.join("/documents/_properties_schema")?)
+                .build()?,
+            StatusCode::OK,
+            false,
+        )
+        .await;
+        assert_eq!(response.schema, None);
+        send_assert(
+            &client,
+            client
+                .post(url.join("/documents")?)
+                .json(&json!({
+                    "documents": [
+                        { "id": "d2", "snippet": "abc", "properties": { "categroy": "news" } },
+                    ]
+                }))
+                .build()?,
+            StatusCode::CREATED,
+            false,
+        )
+        .await;
+        send_assert(
+            &client,
+            client
+                .delete(url.join("/documents/d1/properties/category")?)
+                .build()?,
+            StatusCode::NO_CONTENT,
+            false,
+        )
+        .await;
+
+        Ok(())
+    });
+}
+
+#[derive(Debug, Deserialize)]
+struct IngestionResponse {
+    deduplicated_documents: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CandidatesResponse {
+    documents: Vec<String>,
+}
+
+#[test]
+fn test_ingestion_deduplication_skip() {
+    test_app::<WebApi, _>(
+        Some(toml! {
+            [ingestion]
+            deduplication = "skip"
+        }),
+        |client, url, _| async move {
+            let IngestionResponse {
+                deduplicated_documents,
+            } = send_assert_json(
+                &client,
+                client
+                    .post(url.join("/documents")?)
+                    .json(&json!({
+                        "documents": [ { "id": "d1", "snippet": "Once in a spring there was a fall" } ]
+                    }))
+                    .build()?,
+                StatusCode::CREATED,
+                false,
+            )
+            .await;
+            assert!(deduplicated_documents.is_empty());
+
+            let IngestionResponse {
+                deduplicated_documents,
+            } = send_assert_json(
+                &client,
+                client
+                    .post(url.join("/documents")?)
+                    .json(&json!({
+                        "documents": [ { "id": "d2", "snippet": "once in a spring  there was a fall\n" } ]
+                    }))
+                    .build()?,
+                StatusCode::CREATED,
+                false,
+            )
+            .await;
+            assert_eq!(
+                deduplicated_documents,
+                [json!({ "id": "d2", "duplicate_of": "d1", "action": "skipped" })],
+            );
+
+            let CandidatesResponse { documents } = send_assert_json(
+                &client,
+                client.get(url.join("/documents/_candidates")?).build()?,
+                StatusCode::OK,
+                false,
+            )
+            .await;
+            assert_eq!(documents, ["d1"]);
+            send_assert(
+                &client,
+                client.get(url.join("/documents/d2")?).build()?,
+                StatusCode::BAD_REQUEST,
+                false,
+            )
+            .await;
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_ingestion_deduplication_update_collisions() {
+    test_app::<WebApi, _>(
+        Some(toml! {
+            [ingestion]
+            deduplication = "update"
+        }),
+        |client, url, _| async move {
+            send_assert(
+                &client,
+                client
+                    .post(url.join("/documents")?)
+                    .json(&json!({
+                        "documents": [ { "id": "d1", "snippet": "Once in a spring there was a fall" } ]
+                    }))
+                    .build()?,
+                StatusCode::CREATED,
+                false,
+            )
+            .await;
+
+            // the explicit update of d1 isn't overridden by its duplicate
+            let IngestionResponse {
+                deduplicated_documents,
+            } = send_assert_json(
+                &client,
+                client
+                    .post(url.join("/documents")?)
+                    .json(&json!({
+                        "documents": [
+                            {
+                                "id": "d2",
+                                "snippet": "once in a spring there was a fall",
+                                "properties": { "p": "duplicate" }
+                            },
+                            {
+                                "id": "d1",
+                                "snippet": "Once in a spring there was a fall",
+                                "properties": { "p": "explicit" }
+                            }
+                        ]
+                    }))
+                    .build()?,
+                StatusCode::CREATED,
+                false,
+            )
+            .await;
+            assert_eq!(
+                deduplicated_documents,
+                [json!({ "id": "d2", "duplicate_of": "d1", "action": "skipped" })],
+            );
+            let GetDocument { properties, .. } = send_assert_json(
+                &client,
+                client.get(url.join("/documents/d1")?).build()?,
+                StatusCode::OK,
+                false,
+            )
+            .await;
+            assert_eq!(properties, [("p".to_owned(), json!("explicit"))].into());
+
+            // only one duplicate updates d1
+            let IngestionResponse {
+                deduplicated_documents,
+            } = send_assert_json(
+                &client,
+                client
+                    .post(url.join("/documents")?)
+                    .json(&json!({
+                        "documents": [
+                            { "id": "d2", "snippet": "once in a spring there was a fall" },
+                            { "id": "d3", "snippet": "ONCE in a spring there was a fall" }
+                        ]
+                    }))
+                    .build()?,
+                StatusCode::CREATED,
+                false,
+            )
+            .await;
+            assert_eq!(
+                deduplicated_documents,
+                [
+                    json!({ "id": "d2", "duplicate_of": "d1", "action": "updated" }),
+                    json!({ "id": "d3", "duplicate_of": "d1", "action": "skipped" }),
+                ],
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[derive(Debug, Deserialize)]
+struct GetDocumentsResponse {
+    documents: Vec<Option<GetDocument>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetDocument {
+    id: String,
+    properties: HashMap<String, Value>,
+    tags: Vec<String>,
+}
+
+#[test]
+fn test_get_documents() {
+    test_app::<WebApi, _>(
+        Some(toml! {
+            [ingestion]
+            max_document_batch_size = 4
+        }),
+        |client, url, _| async move {
+            send_assert(
+                &client,
+                client
+                    .post(url.join("/documents")?)
+                    .json(&json!({
+                        "documents": [
+                            { "id": "d1", "snippet": "once in a spring there was a fall", "tags": ["t1"] },
+                            { "id": "d2", "snippet": "fall in a once", "properties": { "p": "v" } },
+                            { "id": "d3", "snippet": "once in a fall there was a spring" }
+                        ]
+                    }))
+                    .build()?,
+                StatusCode::CREATED,
+                false,
+            )
+            .await;
+
+            let GetDocumentsResponse { documents } = send_assert_json(
+                &client,
+                client
+                    .post(url.join("/documents/_get")?)
+                    .json(&json!({ "documents": ["d2", "d4", "d1", "d2"] }))
+                    .build()?,
+                StatusCode::OK,
+                false,
+            )
+            .await;
+            assert_eq!(documents.len(), 4);
+            let d2 = documents[0].as_ref().unwrap();
+            assert_eq!(d2.id, "d2");
+            assert_eq!(d2.properties, [("p".to_owned(), json!("v"))].into());
+            assert!(d2.tags.is_empty());
+            assert!(documents[1].is_none());
+            let d1 = documents[2].as_ref().unwrap();
+            assert_eq!(d1.id, "d1");
+            assert!(d1.properties.is_empty());
+            assert_eq!(d1.tags, ["t1"]);
+            assert_eq!(documents[3].as_ref().unwrap().id, "d2");
+
+            send_assert(
+                &client,
+                client
+                    .post(url.join("/documents/_get")?)
+                    .json(&json!({ "documents": ["d1", "d2", "d3", "d4", "d5"] }))
+                    .build()?,
+                StatusCode::BAD_REQUEST,
+                false,
+            )
+            .await;
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_get_document_etag() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |client, url, _| async move {
+        ingest(&client, &url).await?;
+
+        let response = client.get(url.join("/documents/d1")?).send().await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[ETAG].clone();
+        let document = response.json::<GetDocument>().await?;
+        assert_eq!(document.id, "d1");
+
+        let response = client
+            .get(url.join("/documents/d1")?)
+            .header(IF_NONE_MATCH, etag.clone())
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], etag);
+
+        send_assert(
+            &client,
+            client
+                .put(url.join("/documents/d1/properties")?)
+                .json(&json!({ "properties": { "p": "v" } }))
+                .build()?,
+            StatusCode::NO_CONTENT,
+            false,
+        )
+        .await;
+
+        let response = client
+            .get(url.join("/documents/d1")?)
+            .header(IF_NONE_MATCH, etag.clone())
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_ne!(response.headers()[ETAG], etag);
+
+        Ok(())
+    });
+}
+
+#[test]
+fn test_ingestion_paused() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |client, url, services| async move {
+        ingest(&client, &url).await?;
+
+        let tenant_id = services.tenant.tenant_id.clone();
+        let tenant = services
+            .silo
+            .set_ingestion_enabled(tenant_id.clone(), false)
+            .await?
+            .unwrap();
+        assert!(!tenant.ingestion_enabled);
+
+        let error = send_assert_json::<Error>(
+            &client,
+            client
+                .post(url.join("/documents")?)
+                .json(&json!({ "documents": [ { "id": "d3", "snippet": "snippet 3" } ] }))
+                .build()?,
+            StatusCode::SERVICE_UNAVAILABLE,
+            false,
+        )
+        .await;
+        assert_eq!(error.kind, Kind::IngestionPaused);
+
+        let SemanticSearchResponse { documents } = send_assert_json(
+            &client,
+            client
+                .post(url.join("/semantic_search")?)
+                .json(&json!({ "document": { "query": "spring" } }))
+                .build()?,
+            StatusCode::OK,
+            false,
+        )
+        .await;
+        assert_eq!(
+            documents
+                .iter()
+                .map(|document| document.id.as_str())
+                .collect::<HashSet<_>>(),
+            ["d1", "d2"].into(),
+        );
+
+        let tenant = services
+            .silo
+            .set_ingestion_enabled(tenant_id, true)
+            .await?
+            .unwrap();
+        assert!(tenant.ingestion_enabled);
+        send_assert(
+            &client,
+            client
+                .post(url.join("/documents")?)
+                .json(&json!({ "documents": [ { "id": "d3", "snippet": "snippet 3" } ] }))
+                .build()?,
+            StatusCode::CREATED,
+            false,
+        )
+        .await;
+
+        Ok(())
+    });
+}
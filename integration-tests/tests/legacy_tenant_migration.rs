@@ -73,6 +73,7 @@ fn test_if_the_initializations_work_correctly_for_legacy_tenants() {
                 is_legacy_tenant: true,
                 es_index_name: Some(es_config.index_name.clone()),
                 model: None,
+                ingestion_enabled: None,
             }
             .into(),
             TEST_EMBEDDING_SIZE,
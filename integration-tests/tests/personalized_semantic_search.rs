@@ -276,3 +276,61 @@ fn test_full_personalization_with_inline_history() {
         },
     );
 }
+
+#[test]
+fn test_personalization_with_boost() {
+    test_app::<WebApi, _>(
+        Some(toml! {
+            [semantic_search]
+            score_weights = [0.05, 0.05, 0.9]
+        }),
+        |client, url, _services| async move {
+            ingest(&client, &url).await?;
+            interact(&client, &url).await?;
+
+            send_assert(
+                &client,
+                client
+                    .put(url.join("/documents/d7/properties/boost")?)
+                    .json(&json!({ "property": "high" }))
+                    .build()?,
+                StatusCode::NO_CONTENT,
+                false,
+            )
+            .await;
+            // clamped to the upper bound of the boost
+            send_assert(
+                &client,
+                client
+                    .put(url.join("/documents/d7/properties/boost")?)
+                    .json(&json!({ "property": 1e300 }))
+                    .build()?,
+                StatusCode::NO_CONTENT,
+                false,
+            )
+            .await;
+
+            let SemanticSearchResponse { documents } = send_assert_json(
+                &client,
+                client
+                    .post(url.join("/semantic_search")?)
+                    .json(&json!({
+                        "document": { "id": "d1" },
+                        "count": 5,
+                        "personalize": { "user": { "id": "u1" } }
+                    }))
+                    .build()?,
+                StatusCode::OK,
+                false,
+            )
+            .await;
+            assert_order!(
+                documents,
+                ["d7", "d6", "d4", "d5", "d8"],
+                "unexpected boosted personalized documents: {documents:?}",
+            );
+
+            Ok(())
+        },
+    );
+}
@@ -0,0 +1,311 @@
+// Copyright 2023 Xayn AG
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use anyhow::Error;
+use reqwest::{Client, Method, StatusCode, Url};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use toml::toml;
+use xayn_integration_tests::{
+    send_assert,
+    send_assert_json,
+    start_test_application,
+    test_app,
+    Services,
+    TEST_EMBEDDING_SIZE,
+    UNCHANGED_CONFIG,
+};
+use xayn_test_utils::{
+    assert_approx_eq,
+    asset::{ort, smbert},
+};
+use xayn_web_api::WebApi;
+use xayn_web_api_db_ctrl::tenant::Tenant;
+use xayn_web_api_shared::{elastic::NotFoundAsOptionExt, postgres::QuotedIdentifier};
+
+const DOCUMENTS: [(&str, &str); 3] = [
+    ("d1", "once in a spring there was a fall"),
+    ("d2", "fall in a once"),
+    ("d3", "a spring is not a fall"),
+];
+
+async fn stored_embeddings(services: &Services) -> Result<HashMap<String, Vec<f32>>, Error> {
+    let tenant_schema = QuotedIdentifier::db_name_for_tenant_id(&services.tenant.tenant_id);
+    let query = format!("SELECT document_id, embedding FROM {tenant_schema}.snippet;");
+    let embeddings = sqlx::query_as::<_, (String, Vec<f32>)>(&query)
+        .fetch_all(services.silo.postgres_client())
+        .await?
+        .into_iter()
+        .collect();
+
+    Ok(embeddings)
+}
+
+/// Gets the embedding size of the index, `None` if it doesn't exist.
+async fn index_embedding_size(services: &Services, index: &str) -> Result<Option<usize>, Error> {
+    let elastic = services.silo.elastic_client().with_index(index);
+    let mappings = elastic
+        .query_with_bytes::<Value>(Method::GET, elastic.create_url(["_mapping"], []), None)
+        .await
+        .not_found_as_option()?;
+
+    // an alias is resolved to the name of the index it points to
+    #[allow(clippy::cast_possible_truncation)]
+    let embedding_size = mappings
+        .as_ref()
+        .and_then(Value::as_object)
+        .and_then(|indices| indices.values().next())
+        .and_then(|index| index.pointer("/mappings/properties/embedding/dims"))
+        .and_then(Value::as_u64)
+        .map(|dims| dims as usize);
+
+    Ok(embedding_size)
+}
+
+async fn current_tenant(services: &Services) -> Result<Tenant, Error> {
+    let tenant = services
+        .silo
+        .list_tenants()
+        .await?
+        .into_iter()
+        .find(|tenant| tenant.tenant_id == services.tenant.tenant_id)
+        .unwrap();
+
+    Ok(tenant)
+}
+
+#[derive(Debug, Deserialize)]
+struct ReembeddingResponse {
+    model: String,
+    processed: usize,
+    total: usize,
+    finished: bool,
+}
+
+#[derive(Deserialize)]
+struct TextEmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    kind: String,
+}
+
+async fn ingest_documents(client: &Client, url: &Url) -> Result<(), Error> {
+    send_assert(
+        client,
+        client
+            .post(url.join("/documents")?)
+            .json(&json!({
+                "documents": DOCUMENTS
+                    .iter()
+                    .map(|(id, snippet)| json!({ "id": id, "snippet": snippet }))
+                    .collect::<Vec<_>>()
+            }))
+            .build()?,
+        StatusCode::CREATED,
+        false,
+    )
+    .await;
+
+    Ok(())
+}
+
+async fn reembed(client: &Client, url: &Url, body: Value) -> Result<ReembeddingResponse, Error> {
+    Ok(send_assert_json(
+        client,
+        client
+            .post(url.join("/_ops/reembedding")?)
+            .json(&body)
+            .build()?,
+        StatusCode::OK,
+        false,
+    )
+    .await)
+}
+
+async fn reembed_error(client: &Client, url: &Url, body: Value) -> Result<String, Error> {
+    let response = client
+        .post(url.join("/_ops/reembedding")?)
+        .json(&body)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let ErrorResponse { kind } = response.json().await?;
+
+    Ok(kind)
+}
+
+async fn embed(client: &Client, url: &Url, text: &str) -> Result<Vec<f32>, Error> {
+    let TextEmbeddingResponse { embedding } = send_assert_json(
+        client,
+        client
+            .post(url.join("/embedding")?)
+            .json(&json!({ "text": text }))
+            .build()?,
+        StatusCode::OK,
+        false,
+    )
+    .await;
+
+    Ok(embedding)
+}
+
+#[test]
+fn test_reembedding_into_a_model_with_another_embedding_size() {
+    let smbert = smbert().unwrap().display().to_string();
+    let ort = ort().unwrap().display().to_string();
+    test_app::<WebApi, _>(
+        Some(toml! {
+            [models.smbert]
+            type = "pipeline"
+            directory = smbert
+            runtime = ort
+        }),
+        |client, url, services| async move {
+            ingest_documents(&client, &url).await?;
+            let old_tenant = current_tenant(&services).await?;
+            let tenant_id = old_tenant.tenant_id.to_string();
+
+            let kind = reembed_error(
+                &client,
+                &url,
+                json!({ "tenant_id": tenant_id, "model": "smbert" }),
+            )
+            .await?;
+            assert_eq!(kind, "IngestionNotPaused");
+            services
+                .silo
+                .set_ingestion_enabled(old_tenant.tenant_id.clone(), false)
+                .await?;
+
+            let response = reembed(
+                &client,
+                &url,
+                json!({ "tenant_id": tenant_id, "model": "smbert", "count": 2 }),
+            )
+            .await?;
+            assert_eq!(response.model, "smbert");
+            assert_eq!((response.processed, response.total), (2, 3));
+            assert!(!response.finished);
+
+            // the tenant keeps its model and index until the re-embedding is finished
+            let tenant = current_tenant(&services).await?;
+            assert_eq!(tenant.model, old_tenant.model);
+            assert_eq!(tenant.es_index_name, old_tenant.es_index_name);
+            for embedding in stored_embeddings(&services).await?.values() {
+                assert_eq!(embedding.len(), TEST_EMBEDDING_SIZE);
+            }
+            let kind = reembed_error(
+                &client,
+                &url,
+                json!({ "tenant_id": tenant_id, "model": "default" }),
+            )
+            .await?;
+            assert_eq!(kind, "ReembeddingInProgress");
+
+            // continues after the last re-embedded document
+            let response = reembed(&client, &url, json!({ "tenant_id": tenant_id })).await?;
+            assert_eq!(response.model, "smbert");
+            assert_eq!((response.processed, response.total), (3, 3));
+            assert!(response.finished);
+
+            let tenant = current_tenant(&services).await?;
+            assert_eq!(tenant.model, "smbert");
+            assert_ne!(tenant.es_index_name, old_tenant.es_index_name);
+            assert_eq!(
+                index_embedding_size(&services, &old_tenant.es_index_name).await?,
+                None,
+            );
+            let new_embeddings = stored_embeddings(&services).await?;
+            for (id, snippet) in DOCUMENTS {
+                let embedding = embed(&client, &url, snippet).await?;
+                assert_ne!(embedding.len(), TEST_EMBEDDING_SIZE);
+                assert_eq!(
+                    index_embedding_size(&services, &tenant.es_alias_name).await?,
+                    Some(embedding.len()),
+                );
+                assert_approx_eq!(f32, new_embeddings[id], embedding, epsilon = 1e-6);
+            }
+
+            let response: Value = send_assert_json(
+                &client,
+                client
+                    .post(url.join("/semantic_search")?)
+                    .json(&json!({ "document": { "id": "d1" } }))
+                    .build()?,
+                StatusCode::OK,
+                false,
+            )
+            .await;
+            assert!(!response["documents"].as_array().unwrap().is_empty());
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn test_reembedding_with_a_changed_embedding_prefix() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |client, url, services| async move {
+        ingest_documents(&client, &url).await?;
+        let old_embeddings = stored_embeddings(&services).await?;
+        let old_tenant = current_tenant(&services).await?;
+        let tenant_id = old_tenant.tenant_id.to_string();
+        services
+            .silo
+            .set_ingestion_enabled(old_tenant.tenant_id.clone(), false)
+            .await?;
+
+        // the same model restarted with a different prefix for snippets
+        let handle = start_test_application::<WebApi>(
+            &services,
+            toml! {
+                [tenants]
+                enable_legacy_tenant = false
+
+                [embedding.prefix]
+                snippet = "passage: "
+            },
+        )
+        .await;
+        let url = handle.url();
+
+        let kind =
+            reembed_error(&client, &url, json!({ "tenant_id": tenant_id, "count": 0 })).await?;
+        assert_eq!(kind, "BadRequest");
+
+        let response = reembed(&client, &url, json!({ "tenant_id": tenant_id })).await?;
+        assert_eq!(response.model, "default");
+        assert_eq!((response.processed, response.total), (3, 3));
+        assert!(response.finished);
+
+        let tenant = current_tenant(&services).await?;
+        assert_eq!(tenant.model, "default");
+        assert_ne!(tenant.es_index_name, old_tenant.es_index_name);
+        let new_embeddings = stored_embeddings(&services).await?;
+        for (id, snippet) in DOCUMENTS {
+            let embedding = embed(&client, &url, snippet).await?;
+            assert_eq!(new_embeddings[id].len(), TEST_EMBEDDING_SIZE);
+            assert_approx_eq!(f32, new_embeddings[id], embedding, epsilon = 1e-6);
+            assert_ne!(new_embeddings[id], old_embeddings[id]);
+        }
+
+        handle.stop_and_wait().await?;
+        Ok(())
+    });
+}
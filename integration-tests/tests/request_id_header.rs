@@ -0,0 +1,77 @@
+// Copyright 2023 Xayn AG
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+use xayn_integration_tests::{send_assert, test_app, UNCHANGED_CONFIG};
+use xayn_web_api::WebApi;
+
+#[test]
+fn test_request_id_is_generated() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |client, url, _| async move {
+        let response = send_assert(
+            &client,
+            client.get(url.join("/documents/_candidates")?).build()?,
+            StatusCode::OK,
+            false,
+        )
+        .await;
+        let request_id = response.headers()["x-request-id"].to_str()?;
+        assert!(!request_id.is_empty());
+        Ok(())
+    });
+}
+
+#[test]
+fn test_request_id_is_echoed() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |client, url, _| async move {
+        let response = send_assert(
+            &client,
+            client
+                .get(url.join("/documents/_candidates")?)
+                .header("X-Request-Id", "my-request:42")
+                .build()?,
+            StatusCode::OK,
+            false,
+        )
+        .await;
+        assert_eq!(response.headers()["x-request-id"], "my-request:42");
+        Ok(())
+    });
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    request_id: String,
+}
+
+#[test]
+fn test_request_id_is_echoed_in_errors() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |client, url, _| async move {
+        let response = send_assert(
+            &client,
+            client
+                .get(url.join("/documents/d1/properties")?)
+                .header("X-Request-Id", "my-request:43")
+                .build()?,
+            StatusCode::BAD_REQUEST,
+            false,
+        )
+        .await;
+        assert_eq!(response.headers()["x-request-id"], "my-request:43");
+        let ErrorResponse { request_id } = response.json().await?;
+        assert_eq!(request_id, "my-request:43");
+        Ok(())
+    });
+}
@@ -12,18 +12,33 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::{collections::HashSet, thread};
+use std::{
+    collections::{HashMap, HashSet},
+    thread,
+    time::Duration,
+};
 
 use anyhow::Error;
-use reqwest::{Client, StatusCode};
+use reqwest::{Client, Method, StatusCode};
 use serde::Deserialize;
-use serde_json::json;
+use serde_json::{json, Value};
 use tokio::runtime::Runtime;
 use toml::toml;
 use url::Url;
-use xayn_integration_tests::{send_assert, send_assert_json, test_app, TEST_EMBEDDING_SIZE};
+use xayn_integration_tests::{
+    send_assert,
+    send_assert_json,
+    test_app,
+    TEST_EMBEDDING_SIZE,
+    UNCHANGED_CONFIG,
+};
 use xayn_web_api::WebApi;
-use xayn_web_api_db_ctrl::{elastic, tenant::TenantWithOptionals, OperationResult};
+use xayn_web_api_db_ctrl::{
+    elastic,
+    tenant::{Tenant, TenantWithOptionals},
+    OperationResult,
+    PurgedTenants,
+};
 use xayn_web_api_shared::{
     elastic::ClientWithoutIndex,
     json_object,
@@ -52,6 +67,7 @@ fn test_tenants_can_be_created() {
                         is_legacy_tenant,
                         es_index_name: None,
                         model: None,
+                        ingestion_enabled: None,
                     }
                     .into(),
                 )
@@ -88,10 +104,12 @@ fn test_tenants_can_be_created() {
                     tenant: test_tenant("3", true)?,
                 }
             );
-            assert!(matches!(
-                &results.next().unwrap(),
-                OperationResult::Error { .. }
-            ));
+            assert_eq!(
+                results.next().unwrap(),
+                OperationResult::TenantAlreadyExists {
+                    tenant_id: make_id("1")?,
+                }
+            );
             let OperationResult::ListTenants { tenants } = results.next().unwrap() else {
                 panic!("failed to list tenants");
             };
@@ -122,7 +140,9 @@ fn test_tenants_can_be_created() {
             );
             assert_eq!(
                 results.next().unwrap(),
-                OperationResult::DeleteTenant { tenant: None }
+                OperationResult::TenantNotFound {
+                    tenant_id: make_id("3")?,
+                }
             );
 
             assert_eq!(results.next(), None);
@@ -178,6 +198,18 @@ async fn search(client: &Client, url: &Url) -> Result<HashSet<String>, Error> {
     .collect())
 }
 
+async fn alias_indices(client: &ClientWithoutIndex, alias: &str) -> Result<HashSet<String>, Error> {
+    let client = client.with_index("_alias");
+    let indices = client
+        .query_with_bytes::<HashMap<String, Value>>(
+            Method::GET,
+            client.create_url([alias], []),
+            None,
+        )
+        .await?;
+    Ok(indices.into_keys().collect())
+}
+
 #[test]
 fn test_changing_the_es_index_works() {
     const TEST_INDEX: &str = "test_changing_the_es_index_works";
@@ -201,6 +233,12 @@ fn test_changing_the_es_index_works() {
             }
             let _cleanup = CleanUp(Some(services.silo.elastic_client().clone()));
 
+            let alias = services.tenant.es_alias_name.clone();
+            assert_eq!(
+                alias_indices(services.silo.elastic_client(), &alias).await?,
+                [services.tenant.es_index_name.clone()].into(),
+            );
+
             ingest(&client, &url, vec![("d0", "document 0")]).await?;
             assert_eq!(search(&client, &url).await?, ["d0".to_owned()].into());
 
@@ -236,6 +274,7 @@ fn test_changing_the_es_index_works() {
                     is_legacy_tenant: false,
                     es_index_name: Some(TEST_INDEX.to_owned()),
                     model: None,
+                    ingestion_enabled: None,
                 }
                 .into(),
                 TEST_EMBEDDING_SIZE,
@@ -260,6 +299,10 @@ fn test_changing_the_es_index_works() {
             )
             .await;
             assert_eq!(results, vec![OperationResult::Success]);
+            assert_eq!(
+                alias_indices(services.silo.elastic_client(), &alias).await?,
+                [TEST_INDEX.to_owned()].into(),
+            );
 
             assert_eq!(search(&client, &url).await?, [].into());
 
@@ -281,6 +324,10 @@ fn test_changing_the_es_index_works() {
             )
             .await;
             assert_eq!(results, vec![OperationResult::Success]);
+            assert_eq!(
+                alias_indices(services.silo.elastic_client(), &alias).await?,
+                [services.tenant.es_index_name.clone()].into(),
+            );
 
             assert_eq!(search(&client, &url).await?, ["d0".to_owned()].into());
 
@@ -328,7 +375,8 @@ fn test_creating_tenant_with_alternative_model() {
                         tenant_id: TEST_STRING.parse().unwrap(),
                         is_legacy_tenant: false,
                         es_index_name: None,
-                        model: Some("fake_model".to_owned())
+                        model: Some("fake_model".to_owned()),
+                        ingestion_enabled: None,
                     }
                     .into()
                 }]
@@ -359,7 +407,8 @@ fn test_creating_tenant_with_alternative_model() {
                             tenant_id: TEST_STRING.parse().unwrap(),
                             is_legacy_tenant: false,
                             es_index_name: None,
-                            model: Some("fake_model".to_owned())
+                            model: Some("fake_model".to_owned()),
+                            ingestion_enabled: None,
                         }
                         .into()
                     )
@@ -369,3 +418,119 @@ fn test_creating_tenant_with_alternative_model() {
         },
     );
 }
+
+#[test]
+fn test_silo_uses_configured_pool_size() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |_, _, services| async move {
+        let pool = services.silo.postgres_client();
+        let max_pool_size = services.silo.postgres_config().max_pool_size;
+
+        let mut connections = Vec::new();
+        for _ in 0..max_pool_size {
+            connections.push(pool.acquire().await?);
+        }
+        assert_eq!(pool.size(), u32::from(max_pool_size));
+        assert!(pool.try_acquire().is_none());
+
+        drop(connections);
+        pool.acquire().await?;
+        Ok(())
+    });
+}
+
+#[test]
+fn test_soft_deleted_tenant_can_be_restored() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |client, url, services| async move {
+        let tenant_id = format!("{}_soft", services.test_id).parse::<TenantId>()?;
+        let ManagementResponse { results } = send_assert_json(
+            &client,
+            client
+                .post(url.join("/_ops/silo_management")?)
+                .json(&json!({
+                    "operations": [
+                        { "CreateTenant": { "tenant_id": &tenant_id } },
+                        { "SoftDeleteTenant": { "tenant_id": &tenant_id } },
+                        { "SoftDeleteTenant": { "tenant_id": &tenant_id } },
+                        { "ListTenants": {} },
+                        { "RestoreTenant": { "tenant_id": &tenant_id } },
+                        { "ListTenants": {} },
+                        { "DeleteTenant": { "tenant_id": &tenant_id } },
+                    ]
+                }))
+                .build()?,
+            StatusCode::OK,
+            false,
+        )
+        .await;
+
+        let tenant = Tenant::from(TenantWithOptionals {
+            tenant_id: tenant_id.clone(),
+            is_legacy_tenant: false,
+            es_index_name: None,
+            model: None,
+            ingestion_enabled: None,
+        });
+        let is_listed = |result: &OperationResult| match result {
+            OperationResult::ListTenants { tenants } => tenants.contains(&tenant),
+            result => panic!("unexpected result: {result:?}"),
+        };
+        assert_eq!(
+            results[..3],
+            [
+                OperationResult::CreateTenant {
+                    tenant: tenant.clone()
+                },
+                OperationResult::SoftDeleteTenant {
+                    tenant: Some(tenant.clone())
+                },
+                OperationResult::SoftDeleteTenant { tenant: None },
+            ],
+        );
+        assert!(!is_listed(&results[3]));
+        assert_eq!(
+            results[4],
+            OperationResult::RestoreTenant {
+                tenant: Some(tenant.clone())
+            },
+        );
+        assert!(is_listed(&results[5]));
+        assert_eq!(
+            results[6],
+            OperationResult::DeleteTenant {
+                tenant: Some(tenant)
+            },
+        );
+        Ok(())
+    });
+}
+
+#[test]
+fn test_soft_deleted_tenant_can_be_purged() {
+    test_app::<WebApi, _>(UNCHANGED_CONFIG, |_, _, services| async move {
+        let tenant = Tenant::from(TenantWithOptionals {
+            tenant_id: format!("{}_purge", services.test_id).parse()?,
+            is_legacy_tenant: false,
+            es_index_name: None,
+            model: None,
+            ingestion_enabled: None,
+        });
+        let silo = &services.silo;
+        silo.create_tenant(&tenant).await?;
+        assert_eq!(
+            silo.soft_delete_tenant(tenant.tenant_id.clone()).await?,
+            Some(tenant.clone()),
+        );
+        assert_eq!(
+            silo.purge_expired_tenants().await?,
+            PurgedTenants::default()
+        );
+
+        let silo = silo.clone().with_deleted_tenant_retention(Duration::ZERO);
+        let purged = silo.purge_expired_tenants().await?;
+        assert_eq!(purged.tenants, [tenant.clone()]);
+        assert!(purged.failures.is_empty());
+        assert_eq!(silo.restore_tenant(tenant.tenant_id.clone()).await?, None);
+        assert_eq!(silo.delete_tenant(tenant.tenant_id).await?, None);
+        Ok(())
+    });
+}
@@ -11,6 +11,8 @@
 #
 # You should have received a copy of the GNU Affero General Public License
 # along with this program.  If not, see <https://www.gnu.org/licenses/>.
+import re
+import traceback
 from typing import List, Callable
 from nltk.tokenize import sent_tokenize
 from langchain.text_splitter import (
@@ -97,30 +99,87 @@ class SnippetExtractor(TextSplitterWithBigChunkSplitter):
             length_function=token_len,
         )
 
+# Very frequent words of the languages supported by the NLTK sentence tokenizer, they are
+# used as a cheap heuristic to detect the language of a document.
+STOP_WORDS = {
+    'english': {'the', 'and', 'is', 'are', 'of', 'to', 'that', 'it', 'with', 'this', 'was', 'have', 'be', 'which', 'not'},
+    'german': {'der', 'die', 'das', 'und', 'ist', 'nicht', 'mit', 'ein', 'eine', 'den', 'dem', 'zu', 'auf', 'sich', 'auch'},
+    'french': {'le', 'la', 'les', 'et', 'est', 'des', 'une', 'du', 'pour', 'qui', 'dans', 'pas', 'sur', 'avec', 'au'},
+    'spanish': {'el', 'los', 'las', 'y', 'es', 'del', 'por', 'para', 'como', 'pero', 'su', 'lo', 'muy', 'está', 'también'},
+    'italian': {'il', 'gli', 'è', 'della', 'di', 'che', 'non', 'per', 'sono', 'nel', 'anche', 'alla', 'questo', 'ma', 'delle'},
+    'portuguese': {'o', 'os', 'é', 'do', 'da', 'dos', 'não', 'um', 'uma', 'com', 'em', 'ao', 'pelo', 'mais', 'também'},
+    'dutch': {'de', 'het', 'een', 'en', 'van', 'niet', 'dat', 'op', 'voor', 'met', 'zijn', 'ook', 'te', 'maar', 'wordt'},
+}
+
+def detect_language(text: str, fallback: str) -> str:
+    words = re.findall(r"\w+", text.lower())
+    scores = {
+        language: sum(1 for word in words if word in stop_words)
+        for language, stop_words in STOP_WORDS.items()
+    }
+    language, score = max(scores.items(), key=lambda item: item[1])
+    return language if score > 0 else fallback
+
+def select_snippets(snippets: List[str], max_snippets: int | None, selection: str) -> List[str]:
+    if max_snippets is None or len(snippets) <= max_snippets:
+        return snippets
+    if selection == 'longest':
+        longest = sorted(range(len(snippets)), key=lambda idx: len(snippets[idx]), reverse=True)
+        return [snippets[idx] for idx in sorted(longest[:max_snippets])]
+    return snippets[:max_snippets]
+
 def ok(value: any) -> dict:
     return { 'Ok': value }
 def err(value: any) -> dict:
     return { 'Err': str(value) }
 
 
+def message_pack_stdio(stdin, stdout):
+    # Disable buffer size safety check, we already checked it on the rust side
+    # and keeping that option is sync is another potential source of bugs.
+    unpacker = Unpacker(stdin, max_buffer_size = 0)
+    packer = Packer()
+
+    def write(msg):
+        stdout.write(packer.pack(msg))
+        stdout.flush()
+
+    return unpacker, write
+
+def json_stdio(stdin, stdout):
+    import json
+
+    # one message per line, which makes the pipe human-readable for debugging
+    def read():
+        for line in stdin:
+            yield json.loads(line)
+
+    def write(msg):
+        stdout.write(json.dumps(msg).encode() + b'\n')
+        stdout.flush()
+
+    return read(), write
+
 def run_stdio_client():
     import sys
+    from argparse import ArgumentParser
     tokenizers = {}
 
-    # There is some bad interaction between stdin Binary I/O buffering code
-    # and the Unpacker with can lead to hangs. Using Raw I/O avoids this issue.
-    stdin = sys.stdin.buffer.raw
-    stdout = sys.stdout.buffer
+    parser = ArgumentParser()
+    parser.add_argument('--protocol', choices = ['message_pack', 'json'], default = 'message_pack')
+    args = parser.parse_args()
 
-    # Disable buffer size safety check, we already checked it on the rust side
-    # and keeping that option is sync is another potential source of bugs.
-    unpacker = Unpacker(stdin, max_buffer_size = 0)
-    packer = Packer()
+    stdout = sys.stdout.buffer
+    if args.protocol == 'json':
+        messages, write = json_stdio(sys.stdin.buffer, stdout)
+    else:
+        # There is some bad interaction between stdin Binary I/O buffering code
+        # and the Unpacker with can lead to hangs. Using Raw I/O avoids this issue.
+        messages, write = message_pack_stdio(sys.stdin.buffer.raw, stdout)
 
-    stdout.write(packer.pack("ready"));
-    stdout.flush()
+    write("ready")
 
-    for msg in unpacker:
+    for msg in messages:
         result = None
         try:
             tag = msg['tag']
@@ -129,22 +188,27 @@ def run_stdio_client():
                 tokenizers[cmd['name']] = PreTrainedTokenizerFast(tokenizer_file=cmd['path'])
                 result = ok(True)
             elif tag == 'extract':
+                language = cmd['language']
+                if language == 'auto':
+                    language = detect_language(cmd['document'], cmd['fallback_language'])
                 snippets = SnippetExtractor(
-                    language = cmd['language'],
+                    language = language,
                     chunk_size = cmd['chunk_size'],
                     hard_chunk_size_limit = cmd['hard_chunk_size_limit'],
                     tokenizer = tokenizers[cmd['tokenizer']],
                 ).split_text(cmd['document'])
+                snippets = select_snippets(snippets, cmd['max_snippets'], cmd['snippet_selection'])
                 result = ok(snippets)
             elif tag == 'ping':
                 result = ok(True)
             else:
                 result = err(f"unknown command: {tag}")
         except Exception as error:
+            # the traceback is captured from stderr by the rust side
+            traceback.print_exc()
             result = err(error)
         finally:
-            stdout.write(packer.pack(result))
-            stdout.flush()
+            write(result)
 
 if __name__ == '__main__':
     run_stdio_client()
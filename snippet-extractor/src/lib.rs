@@ -34,7 +34,19 @@
 pub mod pool;
 mod python_child;
 
-use std::{collections::HashMap, io, path::PathBuf};
+use std::{
+    collections::HashMap,
+    env,
+    io,
+    iter::Sum,
+    ops::Add,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use displaydoc::Display;
 use python_child::{PipeCommand, PythonChild};
@@ -48,6 +60,8 @@ pub enum Error {
     Serialization(#[from] rmp_serde::encode::Error),
     /// Deserializing message from snippet extractor failed: {0}
     Deserialization(#[from] rmp_serde::decode::Error),
+    /// Serializing or deserializing json message for snippet extractor failed: {0}
+    Json(#[from] serde_json::Error),
     /// Communication with snippet extractor failed: {0}
     Io(#[from] io::Error),
     /// Snippet extraction failed: {msg}
@@ -60,9 +74,26 @@ pub enum Error {
     UnexpectedErrorResponse { msg: String },
     /// Health check failed and automatic restarts was disabled: {0}
     HealthCheckFailed(Box<Self>),
+    /// Snippet extractor child process failed: {source}, stderr: {stderr}
+    ChildFailed { source: Box<Self>, stderr: String },
 }
 
 impl Error {
+    /// Attaches the recent stderr of a failed child process.
+    fn with_stderr_of(self, child: &mut PythonChild) -> Self {
+        // give the child a moment to exit to capture all of its last words
+        child.wait_for_exit(Duration::from_millis(150));
+        let stderr = child.stderr();
+        if stderr.is_empty() {
+            self
+        } else {
+            Error::ChildFailed {
+                source: Box::new(self),
+                stderr,
+            }
+        }
+    }
+
     fn can_child_be_reused(&self) -> bool {
         matches!(
             self,
@@ -79,13 +110,30 @@ impl Error {
 #[must_use]
 pub struct Config {
     pub python_workspace: PathBuf,
+    /// The language used for sentence splitting.
+    ///
+    /// If set to `"auto"` the language is detected per document.
     pub language: String,
-    // TODO[pmk/now] use relative path buf
+    /// The language used if the language of a document can't be detected.
+    pub fallback_language: String,
+    /// The paths of the tokenizers by name.
+    ///
+    /// Relative paths are resolved against the `asset_root`.
     pub tokenizers: HashMap<String, PathBuf>,
+    /// The directory relative tokenizer paths are resolved against.
+    ///
+    /// Defaults to the `python_workspace` if not set.
+    pub asset_root: Option<PathBuf>,
     pub chunk_size: usize,
     pub hard_chunk_size_limit: usize,
+    /// The maximum number of snippets extracted from a single document.
+    pub max_snippets: Option<usize>,
+    /// Which snippets are kept if a document has more than `max_snippets` snippets.
+    pub snippet_selection: SnippetSelection,
     pub automatically_restart_child: bool,
     pub force_initialization: bool,
+    /// The serialization used for the communication with the child process.
+    pub protocol: Protocol,
     // Hint: From a per-crate design POV this shouldn't be a member of Config,
     //       but from a application level POV this is much more convenient.
     pub pool: pool::Config,
@@ -95,35 +143,124 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             language: "english".into(),
+            fallback_language: "english".into(),
             chunk_size: 500,
             hard_chunk_size_limit: 520,
+            max_snippets: None,
+            snippet_selection: SnippetSelection::First,
             tokenizers: [("default".into(), "./assets/tokenizer.json".into())].into(),
+            asset_root: None,
             python_workspace: "./".into(),
             automatically_restart_child: true,
             force_initialization: true,
+            protocol: Protocol::MessagePack,
             pool: pool::Config::default(),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetSelection {
+    /// Keeps the snippets from the start of the document.
+    First,
+    /// Keeps the longest snippets in their document order.
+    Longest,
+}
+
+/// The serialization used for the communication with the child process.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Protocol {
+    /// Compact binary messages.
+    MessagePack,
+    /// Newline delimited json messages, which are human-readable for debugging.
+    Json,
+}
+
+/// Counters of the child process events of a snippet extractor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExtractorStats {
+    /// The number of spawned child processes, including restarts.
+    pub spawns: u64,
+    /// The number of failed health checks of child processes.
+    pub health_check_failures: u64,
+    /// The number of failed snippet extractions.
+    pub extraction_failures: u64,
+}
+
+impl Add for ExtractorStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            spawns: self.spawns + other.spawns,
+            health_check_failures: self.health_check_failures + other.health_check_failures,
+            extraction_failures: self.extraction_failures + other.extraction_failures,
+        }
+    }
+}
+
+impl Sum for ExtractorStats {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// The counters of a snippet extractor, which can be read while it is in use by the pool.
+#[derive(Debug, Default)]
+struct StatsCounters {
+    spawns: AtomicU64,
+    health_check_failures: AtomicU64,
+    extraction_failures: AtomicU64,
+}
+
+impl StatsCounters {
+    fn increment(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> ExtractorStats {
+        ExtractorStats {
+            spawns: self.spawns.load(Ordering::Relaxed),
+            health_check_failures: self.health_check_failures.load(Ordering::Relaxed),
+            extraction_failures: self.extraction_failures.load(Ordering::Relaxed),
+        }
+    }
+}
+
 pub struct SnippetExtractor {
     config: Config,
     child: Option<PythonChild>,
+    stats: Arc<StatsCounters>,
 }
 
 impl SnippetExtractor {
-    pub fn new(config: Config) -> Result<Self, Error> {
-        for (name, path) in &config.tokenizers {
+    pub fn new(mut config: Config) -> Result<Self, Error> {
+        // the child runs in the python workspace, hence the paths must be absolute
+        let asset_root = config
+            .asset_root
+            .as_ref()
+            .unwrap_or(&config.python_workspace);
+        let asset_root = env::current_dir()?.join(asset_root);
+        for (name, path) in &mut config.tokenizers {
+            *path = asset_root.join(&*path);
             if path.to_str().is_none() {
                 return Err(Error::LoadingTokenizerFailed {
                     msg: format!("tokenizer ({name}) path needs to be utf-8"),
                 });
             }
+            if !path.is_file() {
+                return Err(Error::LoadingTokenizerFailed {
+                    msg: format!("tokenizer ({name}) not found at {}", path.display()),
+                });
+            }
         }
 
         let mut this = Self {
             config,
             child: None,
+            stats: Arc::default(),
         };
 
         if this.config.force_initialization {
@@ -133,6 +270,11 @@ impl SnippetExtractor {
         Ok(this)
     }
 
+    /// Gets the counters of the child process events since the extractor was created.
+    pub fn stats(&self) -> ExtractorStats {
+        self.stats.snapshot()
+    }
+
     pub fn force_initialization(&mut self) -> Result<(), Error> {
         self.with_child(|_child, _config| Ok(()))
     }
@@ -152,8 +294,11 @@ impl SnippetExtractor {
             child.send_command(
                 &Extract {
                     language: &config.language,
+                    fallback_language: &config.fallback_language,
                     chunk_size: config.chunk_size,
                     hard_chunk_size_limit: config.hard_chunk_size_limit,
+                    max_snippets: config.max_snippets,
+                    snippet_selection: config.snippet_selection,
                     tokenizer,
                     document,
                 },
@@ -168,10 +313,13 @@ impl SnippetExtractor {
     ) -> Result<V, Error> {
         let mut child = self.take_child()?;
         let res = func(&mut child, &self.config);
+        if res.is_err() {
+            StatsCounters::increment(&self.stats.extraction_failures);
+        }
         match res {
             Err(err) if !err.can_child_be_reused() => {
                 error!("discarding snippet extractor child process");
-                Err(err)
+                Err(err.with_stderr_of(&mut child))
             }
             reusable => {
                 self.child = Some(child);
@@ -185,6 +333,8 @@ impl SnippetExtractor {
             match child.send_command(&Ping {}, |msg| Error::UnexpectedErrorResponse { msg }) {
                 Ok(_) => Ok(child),
                 Err(error) => {
+                    let error = error.with_stderr_of(&mut child);
+                    StatsCounters::increment(&self.stats.health_check_failures);
                     if self.config.automatically_restart_child {
                         error!("Health check failed: {}", error);
                         self.spawn_child()
@@ -198,10 +348,11 @@ impl SnippetExtractor {
         }
     }
 
-    fn spawn_child(&self) -> Result<PythonChild, Error> {
+    fn spawn_child(&mut self) -> Result<PythonChild, Error> {
         let mut child = PythonChild::spawn(
             &self.config.python_workspace,
             "./python_src/snippet_extractor.py",
+            self.config.protocol,
         )?;
 
         let ready = child.read_message::<String, Error>()?;
@@ -213,6 +364,7 @@ impl SnippetExtractor {
                 Error::LoadingTokenizerFailed { msg }
             })?;
         }
+        StatsCounters::increment(&self.stats.spawns);
 
         Ok(child)
     }
@@ -240,8 +392,11 @@ impl PipeCommand for LoadTokenizer<'_> {
 #[derive(Serialize)]
 struct Extract<'a> {
     language: &'a str,
+    fallback_language: &'a str,
     chunk_size: usize,
     hard_chunk_size_limit: usize,
+    max_snippets: Option<usize>,
+    snippet_selection: SnippetSelection,
     tokenizer: &'a str,
     document: &'a str,
 }
@@ -250,3 +405,124 @@ impl PipeCommand for Extract<'_> {
     type Value = Vec<String>;
     const TAG: &'static str = "extract";
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process};
+
+    use xayn_test_utils::workspace::find_workspace_dir;
+
+    use super::*;
+
+    #[test]
+    fn test_stats_count_restarts() -> Result<(), Error> {
+        let workspace = find_workspace_dir();
+        let mut extractor = SnippetExtractor::new(Config {
+            tokenizers: [(
+                "default".into(),
+                workspace.join("assets/xaynia_v0201/tokenizer.json"),
+            )]
+            .into(),
+            python_workspace: workspace.join("snippet-extractor"),
+            ..Config::default()
+        })?;
+        assert_eq!(
+            extractor.stats(),
+            ExtractorStats {
+                spawns: 1,
+                ..ExtractorStats::default()
+            }
+        );
+
+        extractor.child.as_mut().unwrap().kill();
+        extractor.extract_snippet("default", "This is a test.")?;
+        assert_eq!(
+            extractor.stats(),
+            ExtractorStats {
+                spawns: 2,
+                health_check_failures: 1,
+                extraction_failures: 0,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stderr_of_dying_child_is_captured() {
+        let stub = env::temp_dir().join(format!("dying_snippet_extractor_{}.py", process::id()));
+        fs::write(
+            &stub,
+            "import sys\n\
+             print('\"ready\"', flush=True)\n\
+             sys.stdin.readline()\n\
+             raise RuntimeError('stub child died')\n",
+        )
+        .unwrap();
+
+        let workspace = find_workspace_dir().join("snippet-extractor");
+        let mut child = PythonChild::spawn(workspace, &stub, Protocol::Json).unwrap();
+        let ready = child.read_message::<String, Error>().unwrap();
+        assert_eq!(ready, "ready");
+        let error = child
+            .send_command(&Ping {}, |msg| Error::UnexpectedErrorResponse { msg })
+            .unwrap_err()
+            .with_stderr_of(&mut child);
+        fs::remove_file(stub).unwrap();
+
+        let Error::ChildFailed { stderr, .. } = error else {
+            panic!("unexpected error: {error}");
+        };
+        assert!(stderr.contains("Traceback (most recent call last):"));
+        assert!(stderr.contains("RuntimeError: stub child died"));
+    }
+
+    #[test]
+    fn test_relative_tokenizer_paths_are_resolved() -> Result<(), Error> {
+        let workspace = env::temp_dir().join(format!("snippet_extractor_{}", process::id()));
+        fs::create_dir_all(workspace.join("assets"))?;
+        fs::write(workspace.join("assets/tokenizer.json"), "{}")?;
+        let config = Config {
+            tokenizers: [
+                ("relative".into(), "./assets/tokenizer.json".into()),
+                ("absolute".into(), workspace.join("assets/tokenizer.json")),
+            ]
+            .into(),
+            python_workspace: workspace.clone(),
+            force_initialization: false,
+            ..Config::default()
+        };
+
+        let extractor = SnippetExtractor::new(config.clone())?;
+        let tokenizer = workspace.join("./assets/tokenizer.json");
+        assert_eq!(extractor.config.tokenizers["relative"], tokenizer);
+        assert_eq!(
+            extractor.config.tokenizers["absolute"],
+            workspace.join("assets/tokenizer.json"),
+        );
+
+        let extractor = SnippetExtractor::new(Config {
+            tokenizers: [("relative".into(), "./tokenizer.json".into())].into(),
+            asset_root: Some(workspace.join("assets")),
+            ..config.clone()
+        })?;
+        assert_eq!(
+            extractor.config.tokenizers["relative"],
+            workspace.join("assets/./tokenizer.json"),
+        );
+
+        let error = SnippetExtractor::new(Config {
+            tokenizers: [("missing".into(), "./assets/missing.json".into())].into(),
+            ..config
+        })
+        .err()
+        .unwrap();
+        fs::remove_dir_all(workspace)?;
+        assert!(
+            matches!(&error, Error::LoadingTokenizerFailed { msg } if msg.contains("(missing) not found")),
+            "unexpected error: {error}",
+        );
+
+        Ok(())
+    }
+}
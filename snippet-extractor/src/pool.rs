@@ -12,7 +12,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::{io, time::Duration};
+use std::{io, sync::Arc, time::Duration};
 
 use deadpool::{
     unmanaged::{Object, Pool, PoolConfig, PoolError},
@@ -24,7 +24,7 @@ use serde::{Deserialize, Serialize};
 use tokio::task::spawn_blocking;
 use xayn_web_api_shared::serde::serde_duration_in_config;
 
-use crate::{Error, SnippetExtractor};
+use crate::{Error, ExtractorStats, SnippetExtractor, StatsCounters};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
@@ -46,6 +46,7 @@ impl Default for Config {
 
 pub struct SnippetExtractorPool {
     pool: Pool<SnippetExtractor>,
+    stats: Vec<Arc<StatsCounters>>,
 }
 
 impl SnippetExtractorPool {
@@ -66,16 +67,44 @@ impl SnippetExtractorPool {
             runtime: Some(Runtime::Tokio1),
         });
 
+        let mut stats = Vec::with_capacity(max_size);
         for _ in 0..max_size {
             let extractor = SnippetExtractor::new(config.clone())?;
+            stats.push(extractor.stats.clone());
             pool.try_add(extractor).map_err(|(_, err)| err).unwrap(/* can't happen */);
         }
-        Ok(Self { pool })
+        Ok(Self { pool, stats })
     }
 
     pub async fn get(&self) -> Result<PooledSnippetExtractor, PoolAcquisitionError> {
         Ok(self.pool.get().await?.into())
     }
+
+    /// The number of snippet extractors, i.e. the max number of concurrent extractions.
+    pub fn max_size(&self) -> usize {
+        self.pool.status().max_size
+    }
+
+    /// Gets the counters of the child process events summed over all snippet extractors,
+    /// including the ones currently in use.
+    pub fn stats(&self) -> ExtractorStats {
+        self.stats.iter().map(|stats| stats.snapshot()).sum()
+    }
+
+    /// Extracts the snippets with the next available snippet extractor.
+    ///
+    /// The blocking communication with the python child runs on tokio's blocking thread pool.
+    pub async fn extract_snippet_async(
+        &self,
+        tokenizer: String,
+        document: String,
+    ) -> Result<Vec<String>, PoolExtractionError> {
+        Ok(self
+            .get()
+            .await?
+            .extract_snippet(tokenizer, document)
+            .await?)
+    }
 }
 
 /// Failed to acquire snippet extractor: {0}
@@ -88,6 +117,14 @@ impl PoolAcquisitionError {
     }
 }
 
+#[derive(Debug, Display, thiserror::Error, From)]
+pub enum PoolExtractionError {
+    /// {0}
+    Acquisition(PoolAcquisitionError),
+    /// {0}
+    Extraction(Error),
+}
+
 #[derive(Deref, DerefMut, From)]
 pub struct PooledSnippetExtractor(Object<SnippetExtractor>);
 
@@ -13,10 +13,12 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use std::{
-    io::{self, BufReader, Write},
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Read, Write},
     path::Path,
     process::{Child, ChildStdin, ChildStdout, Command, Stdio},
-    thread,
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
     time::{Duration, Instant},
 };
 
@@ -25,67 +27,160 @@ use rmp_serde::{
     decode::ReadReader,
 };
 use serde::{de::DeserializeOwned, Serialize};
+use tracing::warn;
+
+use crate::Protocol;
+
+/// The maximum number of recent stderr lines kept of a child process.
+const STDERR_LINES: usize = 20;
+/// The maximum number of bytes kept of a single stderr line.
+const STDERR_LINE_BYTES: usize = 1024;
 
 pub(crate) struct PythonChild {
     child: Child,
-    // Hint: We always write the whole package at once, so no point in using a BufWriter
-    write_to: Option<rmp_serde::Serializer<ChildStdin, StructMapConfig<DefaultConfig>>>,
-    read_from: Option<rmp_serde::Deserializer<ReadReader<BufReader<ChildStdout>>, DefaultConfig>>,
+    write_to: Option<Writer>,
+    read_from: Option<Reader>,
+    stderr: Arc<Mutex<VecDeque<String>>>,
+    stderr_reader: JoinHandle<()>,
+}
+
+// Hint: We always write the whole package at once, so no point in using a BufWriter
+enum Writer {
+    MessagePack(rmp_serde::Serializer<ChildStdin, StructMapConfig<DefaultConfig>>),
+    Json(ChildStdin),
+}
+
+enum Reader {
+    MessagePack(rmp_serde::Deserializer<ReadReader<BufReader<ChildStdout>>, DefaultConfig>),
+    Json(BufReader<ChildStdout>),
 }
 
 impl PythonChild {
     pub(crate) fn spawn(
         workspace: impl AsRef<Path>,
         python_file: impl AsRef<Path>,
+        protocol: Protocol,
     ) -> Result<Self, io::Error> {
+        let protocol_arg = match protocol {
+            Protocol::MessagePack => "message_pack",
+            Protocol::Json => "json",
+        };
         let mut child = Command::new("pipenv")
             .args(["run", "python"])
             .arg(python_file.as_ref())
+            .args(["--protocol", protocol_arg])
             .current_dir(workspace.as_ref())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
             .spawn()?;
 
+        let stderr = Arc::default();
+        let stderr_reader = spawn_stderr_reader(
+            child.stderr.take().unwrap(/* Command.stderr(piped) was used */),
+            Arc::clone(&stderr),
+        )?;
+
         let write_to = child.stdin.take().unwrap(/* Command.stdin(piped) was used */);
-        let write_to = rmp_serde::Serializer::new(write_to).with_struct_map();
         let read_from = child.stdout.take().unwrap(/* Command.stdout(piped) was used */);
-        let read_from = rmp_serde::Deserializer::new(BufReader::new(read_from));
+        let read_from = BufReader::new(read_from);
+        let (write_to, read_from) = match protocol {
+            Protocol::MessagePack => (
+                Writer::MessagePack(rmp_serde::Serializer::new(write_to).with_struct_map()),
+                Reader::MessagePack(rmp_serde::Deserializer::new(read_from)),
+            ),
+            Protocol::Json => (Writer::Json(write_to), Reader::Json(read_from)),
+        };
         Ok(PythonChild {
             child,
             write_to: Some(write_to),
             read_from: Some(read_from),
+            stderr,
+            stderr_reader,
         })
     }
 
     pub(crate) fn read_message<V, E>(&mut self) -> Result<V, E>
     where
         V: DeserializeOwned,
-        E: From<rmp_serde::decode::Error>,
+        E: From<rmp_serde::decode::Error> + From<serde_json::Error> + From<io::Error>,
     {
-        let deserializer = self.read_from.as_mut().unwrap(/* only None in Drop */);
-        V::deserialize(deserializer).map_err(E::from)
+        match self.read_from.as_mut().unwrap(/* only None in Drop */) {
+            Reader::MessagePack(deserializer) => V::deserialize(deserializer).map_err(E::from),
+            Reader::Json(reader) => {
+                let mut line = String::new();
+                if reader.read_line(&mut line)? == 0 {
+                    return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+                }
+                serde_json::from_str(&line).map_err(E::from)
+            }
+        }
     }
 
     pub(crate) fn write_message<M, E>(&mut self, msg: &M) -> Result<(), E>
     where
         M: Serialize,
-        E: From<rmp_serde::encode::Error> + From<io::Error>,
+        E: From<rmp_serde::encode::Error> + From<serde_json::Error> + From<io::Error>,
     {
-        let serializer = self.write_to.as_mut().unwrap(/* only None in Drop */);
-        msg.serialize(&mut *serializer)?;
-        serializer.get_mut().flush()?;
+        match self.write_to.as_mut().unwrap(/* only None in Drop */) {
+            Writer::MessagePack(serializer) => {
+                msg.serialize(&mut *serializer)?;
+                serializer.get_mut().flush()?;
+            }
+            Writer::Json(writer) => {
+                let mut line = serde_json::to_vec(msg)?;
+                line.push(b'\n');
+                writer.write_all(&line)?;
+                writer.flush()?;
+            }
+        }
         Ok(())
     }
 
+    /// Returns the recently captured stderr lines of the child.
+    pub(crate) fn stderr(&self) -> String {
+        let mut stderr = self.stderr.lock().unwrap(/* reader doesn't panic */);
+        stderr.make_contiguous().join("\n")
+    }
+
+    /// Waits until the child exited and its stderr is fully captured or the timeout is reached.
+    pub(crate) fn wait_for_exit(&mut self, timeout: Duration) {
+        let start = Instant::now();
+        while start.elapsed() < timeout
+            && (matches!(self.child.try_wait(), Ok(None)) || !self.stderr_reader.is_finished())
+        {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn kill(&mut self) {
+        self.child.kill().unwrap();
+        self.child.wait().unwrap();
+    }
+
     pub(crate) fn send_command<C, M, E>(&mut self, cmd: &C, map_err: M) -> Result<C::Value, E>
     where
         C: PipeCommand,
         M: Fn(String) -> E,
-        E: From<rmp_serde::encode::Error> + From<rmp_serde::decode::Error> + From<io::Error>,
+        E: From<rmp_serde::encode::Error>
+            + From<rmp_serde::decode::Error>
+            + From<serde_json::Error>
+            + From<io::Error>,
     {
+        // only keep the stderr related to this command
+        self.stderr.lock().unwrap(/* reader doesn't panic */).clear();
         self.write_message::<_, E>(&Message { tag: C::TAG, cmd })?;
         self.read_message::<Result<C::Value, String>, E>()?
-            .map_err(map_err)
+            .map_err(|msg| {
+                // the stderr is captured concurrently and might miss the last lines
+                let stderr = self.stderr();
+                if stderr.is_empty() {
+                    map_err(msg)
+                } else {
+                    map_err(format!("{msg}\nstderr:\n{stderr}"))
+                }
+            })
     }
 }
 
@@ -105,6 +200,55 @@ impl Drop for PythonChild {
     }
 }
 
+/// Logs the stderr and captures its most recent lines in a ring buffer.
+fn spawn_stderr_reader(
+    stderr: impl Read + Send + 'static,
+    lines: Arc<Mutex<VecDeque<String>>>,
+) -> Result<JoinHandle<()>, io::Error> {
+    thread::Builder::new()
+        .name("snippet-extractor-stderr".into())
+        .spawn(move || {
+            let mut stderr = BufReader::new(stderr);
+            let mut buffer = Vec::with_capacity(STDERR_LINE_BYTES);
+            loop {
+                buffer.clear();
+                #[allow(clippy::cast_possible_truncation)]
+                let limit = STDERR_LINE_BYTES as u64;
+                match (&mut stderr).take(limit).read_until(b'\n', &mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {}
+                }
+                if buffer.last() != Some(&b'\n') && discard_line(&mut stderr).is_err() {
+                    break;
+                }
+                let line = String::from_utf8_lossy(&buffer).trim_end().to_owned();
+                warn!(stderr = %line, "snippet extractor child");
+
+                let mut lines = lines.lock().unwrap(/* reader doesn't panic */);
+                if lines.len() == STDERR_LINES {
+                    lines.pop_front();
+                }
+                lines.push_back(line);
+            }
+        })
+}
+
+/// Skips the rest of the current line without buffering it.
+fn discard_line(reader: &mut impl BufRead) -> Result<(), io::Error> {
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            return Ok(());
+        }
+        if let Some(end) = available.iter().position(|&byte| byte == b'\n') {
+            reader.consume(end + 1);
+            return Ok(());
+        }
+        let len = available.len();
+        reader.consume(len);
+    }
+}
+
 pub(crate) trait PipeCommand: Serialize {
     type Value: DeserializeOwned;
     const TAG: &'static str;
@@ -115,3 +259,27 @@ struct Message<'a, T: PipeCommand> {
     tag: &'a str,
     cmd: &'a T,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_stderr_lines_are_bounded() {
+        let long_line = "a".repeat(3 * STDERR_LINE_BYTES);
+        let stderr = Cursor::new(format!("{long_line}\nshort\n{long_line}"));
+        let lines = Arc::default();
+        spawn_stderr_reader(stderr, Arc::clone(&lines))
+            .unwrap()
+            .join()
+            .unwrap();
+
+        let lines = lines.lock().unwrap();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], long_line[..STDERR_LINE_BYTES]);
+        assert_eq!(lines[1], "short");
+        assert_eq!(lines[2], long_line[..STDERR_LINE_BYTES]);
+    }
+}
@@ -12,11 +12,24 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::{
+    cmp::Reverse,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::task::spawn_blocking;
 use xayn_snippet_extractor::{
-    pool::{self, SnippetExtractorPool},
+    pool::{self, PoolExtractionError, SnippetExtractorPool},
     Config,
     Error,
+    ExtractorStats,
+    Protocol,
     SnippetExtractor,
+    SnippetSelection,
 };
 use xayn_test_utils::workspace::find_workspace_dir;
 
@@ -76,19 +89,130 @@ fn test_snippet_extraction_works() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn test_snippet_extraction_is_capped() -> Result<(), Error> {
+    let workspace = find_workspace_dir();
+    let config = |snippet_selection| Config {
+        chunk_size: 50,
+        hard_chunk_size_limit: 55,
+        max_snippets: Some(3),
+        snippet_selection,
+        tokenizers: [(
+            "default".into(),
+            workspace.join("assets/xaynia_v0201/tokenizer.json"),
+        )]
+        .into(),
+        python_workspace: workspace.join("snippet-extractor"),
+        ..Default::default()
+    };
+    let mut uncapped = SnippetExtractor::new(Config {
+        max_snippets: None,
+        ..config(SnippetSelection::First)
+    })?;
+    let mut first = SnippetExtractor::new(config(SnippetSelection::First))?;
+    let mut longest = SnippetExtractor::new(config(SnippetSelection::Longest))?;
+
+    let all = uncapped.extract_snippet("default", TEST_TEXT)?;
+    assert!(all.len() > 3);
+    assert_eq!(first.extract_snippet("default", TEST_TEXT)?, all[..3]);
+
+    let mut expected = all.iter().enumerate().collect::<Vec<_>>();
+    expected.sort_by_key(|(_, snippet)| Reverse(snippet.chars().count()));
+    expected.truncate(3);
+    expected.sort_by_key(|(idx, _)| *idx);
+    let expected = expected
+        .into_iter()
+        .map(|(_, snippet)| snippet.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(longest.extract_snippet("default", TEST_TEXT)?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_snippet_extraction_with_json_protocol() -> Result<(), Error> {
+    let workspace = find_workspace_dir();
+    let config = |protocol| Config {
+        chunk_size: 50,
+        hard_chunk_size_limit: 55,
+        tokenizers: [(
+            "default".into(),
+            workspace.join("assets/xaynia_v0201/tokenizer.json"),
+        )]
+        .into(),
+        python_workspace: workspace.join("snippet-extractor"),
+        protocol,
+        ..Default::default()
+    };
+    let mut message_pack = SnippetExtractor::new(config(Protocol::MessagePack))?;
+    let mut json = SnippetExtractor::new(config(Protocol::Json))?;
+
+    let snippets = json.extract_snippet("default", TEST_TEXT)?;
+    assert!(snippets.len() > 1);
+    assert_eq!(
+        snippets,
+        message_pack.extract_snippet("default", TEST_TEXT)?,
+    );
+
+    Ok(())
+}
+
+const FRENCH_TEXT: &str = "M. Dupont habite à Paris depuis longtemps. Il aime les croissants et le café noir. \
+Le matin, il lit le journal avec Mme. Martin qui est sa voisine. Ils parlent de la politique et du temps.";
+
+#[test]
+fn test_snippet_extraction_detects_language() -> Result<(), Error> {
+    let workspace = find_workspace_dir();
+    let config = |language: &str| Config {
+        language: language.into(),
+        fallback_language: "english".into(),
+        chunk_size: 20,
+        hard_chunk_size_limit: 25,
+        tokenizers: [(
+            "default".into(),
+            workspace.join("assets/xaynia_v0201/tokenizer.json"),
+        )]
+        .into(),
+        python_workspace: workspace.join("snippet-extractor"),
+        ..Default::default()
+    };
+    let mut auto = SnippetExtractor::new(config("auto"))?;
+    let mut french = SnippetExtractor::new(config("french"))?;
+    let mut english = SnippetExtractor::new(config("english"))?;
+
+    let snippets = auto.extract_snippet("default", FRENCH_TEXT)?;
+    assert!(snippets.len() > 1);
+    assert_eq!(snippets, french.extract_snippet("default", FRENCH_TEXT)?);
+    assert_eq!(
+        auto.extract_snippet("default", TEST_TEXT)?,
+        english.extract_snippet("default", TEST_TEXT)?,
+    );
+    // nothing to detect, uses the fallback language
+    assert_eq!(
+        auto.extract_snippet("default", "1234 5678")?,
+        english.extract_snippet("default", "1234 5678")?,
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_extractor_can_be_reused() {
     let limit_to_one_thread = (num_cpus::get() as f32).recip() / 2.;
     let workspace = find_workspace_dir();
     let pool = SnippetExtractorPool::new(&Config {
         language: "english".into(),
+        fallback_language: "english".into(),
         chunk_size: 50,
         hard_chunk_size_limit: 55,
+        max_snippets: None,
+        snippet_selection: SnippetSelection::First,
         tokenizers: [(
             "default".into(),
             workspace.join("assets/xaynia_v0201/tokenizer.json"),
         )]
         .into(),
+        asset_root: None,
         python_workspace: workspace.join("snippet-extractor"),
         pool: pool::Config {
             threads_per_cpu: limit_to_one_thread,
@@ -96,6 +220,7 @@ async fn test_extractor_can_be_reused() {
         },
         automatically_restart_child: false,
         force_initialization: true,
+        protocol: Protocol::MessagePack,
     })
     .unwrap();
 
@@ -111,6 +236,101 @@ async fn test_extractor_can_be_reused() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_concurrent_extractions_are_capped() {
+    let limit_to_two_threads = (num_cpus::get() as f32).recip() * 2.;
+    let workspace = find_workspace_dir();
+    let pool = Arc::new(
+        SnippetExtractorPool::new(&Config {
+            chunk_size: 50,
+            hard_chunk_size_limit: 55,
+            tokenizers: [(
+                "default".into(),
+                workspace.join("assets/xaynia_v0201/tokenizer.json"),
+            )]
+            .into(),
+            python_workspace: workspace.join("snippet-extractor"),
+            pool: pool::Config {
+                threads_per_cpu: limit_to_two_threads,
+                acquisition_timeout: Duration::from_secs(3),
+            },
+            automatically_restart_child: false,
+            force_initialization: true,
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+    let mut busy = Vec::new();
+    for _ in 0..pool.max_size() {
+        busy.push(pool.get().await.unwrap());
+    }
+    let error = pool
+        .extract_snippet_async("default".into(), TEST_TEXT.into())
+        .await
+        .unwrap_err();
+    assert!(matches!(error, PoolExtractionError::Acquisition(error) if error.is_timeout()));
+    drop(busy);
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let extractions = (0..8)
+        .map(|_| {
+            let pool = pool.clone();
+            let in_flight = in_flight.clone();
+            let peak = peak.clone();
+            tokio::spawn(async move {
+                let mut extractor = pool.get().await.unwrap();
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(current, Ordering::SeqCst);
+                spawn_blocking(move || {
+                    let snippets =
+                        SnippetExtractor::extract_snippet(&mut extractor, "default", TEST_TEXT);
+                    // count down before the extractor is returned to the pool
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    drop(extractor);
+                    snippets
+                })
+                .await
+            })
+        })
+        .collect::<Vec<_>>();
+    for extraction in extractions {
+        assert!(!extraction.await.unwrap().unwrap().unwrap().is_empty());
+    }
+    assert!((1..=pool.max_size()).contains(&peak.load(Ordering::SeqCst)));
+}
+
+#[tokio::test]
+async fn test_pool_stats_are_aggregated() {
+    let limit_to_two_threads = (num_cpus::get() as f32).recip() * 2.;
+    let workspace = find_workspace_dir();
+    let pool = SnippetExtractorPool::new(&Config {
+        tokenizers: [(
+            "default".into(),
+            workspace.join("assets/xaynia_v0201/tokenizer.json"),
+        )]
+        .into(),
+        python_workspace: workspace.join("snippet-extractor"),
+        pool: pool::Config {
+            threads_per_cpu: limit_to_two_threads,
+            ..pool::Config::default()
+        },
+        force_initialization: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    // the stats of checked out extractors are included
+    let _busy = pool.get().await.unwrap();
+    assert_eq!(
+        pool.stats(),
+        ExtractorStats {
+            spawns: pool.max_size() as u64,
+            ..ExtractorStats::default()
+        },
+    );
+}
+
 // from BAnz AT 13.07.2023 B1 page 3
 const SAMPLE_TEXT: &str = "6.2  Die  Vergabe  von  Unteraufträgen  hat  nach  Möglichkeit  im  Wettbewerb  zu  erfolgen.  Bei  der  Einholung  von  An-
 geboten  für  Unteraufträge  sind  kleine  und  mittlere,  nicht  konzerngebundene  Unternehmen  soweit  möglich  zu  betei-
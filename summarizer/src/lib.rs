@@ -30,8 +30,25 @@
     clippy::must_use_candidate
 )]
 
+mod sentences;
+mod stop_words;
 mod summarizers;
 
+use displaydoc::Display;
+pub use sentences::split_sentences;
+use thiserror::Error;
+
+/// The potential errors of [`summarize()`].
+#[derive(Debug, Display, Error)]
+pub enum SummarizeError {
+    /// The source doesn't contain any text to summarize
+    EmptyInput,
+    /// Failed to fetch the source: {0}
+    Fetch(String),
+    /// Failed to extract the readable text of the source: {0}
+    Readability(String),
+}
+
 /// Summarizes a source to the amount of sentences specified in the `config`.
 ///
 /// Currently we have 2 `summarizer` implementations, naive and rank based.
@@ -42,6 +59,12 @@ mod summarizers;
 /// When using url, we will attempt to fetch the content first.
 /// When using html or url, the content will first pass-through a readability implementation from Mozilla.
 ///
+/// If the summarizer can't find any relevant sentences, the readable text of the source is returned.
+///
+/// # Errors
+///
+/// Fails if the source doesn't contain any text or if its readable text can't be obtained.
+///
 /// # Examples
 ///
 /// Basic usage:
@@ -55,34 +78,88 @@ mod summarizers;
 ///         text: "Lorem ispum dolor si amet...".to_string(),
 ///     },
 ///     &Config::default(),
-/// );
+/// )
+/// .unwrap();
 /// ```
-pub fn summarize(summarizer: &Summarizer, source: &Source, config: &Config) -> String {
-    let text = source.to_readable_text();
+pub fn summarize(
+    summarizer: &Summarizer,
+    source: &Source,
+    config: &Config,
+) -> Result<String, SummarizeError> {
+    let text = source.to_readable_text()?;
+    if text.trim().is_empty() {
+        return Err(SummarizeError::EmptyInput);
+    }
+
     let summary = match summarizer {
-        Summarizer::Naive => summarizers::naive::summarize(&text, config.num_sentences),
+        Summarizer::Naive => {
+            summarizers::naive::summarize(&text, &config.language, config.num_sentences)
+        }
         Summarizer::RankBased => {
-            summarizers::rank_based::summarize(&text, &[], config.num_sentences)
+            let stop_words = config.stop_words.to_lowercase();
+            let stop_words = stop_words.iter().map(String::as_str).collect::<Vec<_>>();
+            summarizers::rank_based::summarize(
+                &text,
+                &config.language,
+                &stop_words,
+                config.num_sentences,
+            )
         }
     };
 
     if summary.is_empty() {
-        text
+        Ok(text)
     } else {
-        summary
+        Ok(summary)
     }
 }
 
 /// Configures how many sentences should be kept, from the original source.
 /// Defaults to 4 sentences.
-#[derive(Copy, Clone, Debug)]
+///
+/// The sentences are split according to the `language`, see [`split_sentences()`], which
+/// defaults to english.
+///
+/// The rank based summarizer ignores the `stop_words` when comparing sentences, which defaults
+/// to no stop words.
+#[derive(Clone, Debug)]
 pub struct Config {
     pub num_sentences: usize,
+    pub language: String,
+    pub stop_words: StopWords,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Config { num_sentences: 4 }
+        Config {
+            num_sentences: 4,
+            language: "english".into(),
+            stop_words: StopWords::default(),
+        }
+    }
+}
+
+/// The words which don't contribute to the similarity of sentences.
+#[derive(Clone, Debug, Default)]
+pub enum StopWords {
+    #[default]
+    None,
+    English,
+    German,
+    Custom(Vec<String>),
+}
+
+impl StopWords {
+    fn to_lowercase(&self) -> Vec<String> {
+        let words = match self {
+            StopWords::None => &[],
+            StopWords::English => stop_words::ENGLISH,
+            StopWords::German => stop_words::GERMAN,
+            StopWords::Custom(words) => {
+                return words.iter().map(|word| word.to_lowercase()).collect();
+            }
+        };
+        words.iter().map(ToString::to_string).collect()
     }
 }
 
@@ -93,9 +170,11 @@ pub enum Source {
 }
 
 impl Source {
-    fn to_readable_text(&self) -> String {
+    // Hint: fetching urls and parsing html will be fallible
+    #[allow(clippy::unnecessary_wraps)]
+    fn to_readable_text(&self) -> Result<String, SummarizeError> {
         match self {
-            Source::PlainText { text } => text.clone(),
+            Source::PlainText { text } => Ok(text.clone()),
         }
     }
 }
@@ -105,3 +184,77 @@ pub enum Summarizer {
     Naive,
     RankBased,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain_text(text: &str) -> Source {
+        Source::PlainText {
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_summarize_empty_input() {
+        for summarizer in [Summarizer::Naive, Summarizer::RankBased] {
+            for text in ["", " \n\t "] {
+                assert!(matches!(
+                    summarize(&summarizer, &plain_text(text), &Config::default()),
+                    Err(SummarizeError::EmptyInput),
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_summarize_with_stop_words() {
+        let source = plain_text(
+            "It is what it is and it was what it was. \
+            Rust compiles the code into fast binaries. \
+            The compiler checks the code of the binaries. \
+            It is and it was and it is what it was. \
+            The cats sleep all of the day. \
+            It was what it is. \
+            Fast binaries need the compiler. \
+            The day is long for the cats.",
+        );
+        let summarize = |stop_words| {
+            let config = Config {
+                num_sentences: 2,
+                stop_words,
+                ..Config::default()
+            };
+            summarize(&Summarizer::RankBased, &source, &config).unwrap()
+        };
+
+        assert_eq!(
+            summarize(StopWords::None),
+            "Rust compiles the code into fast binaries. It is and it was and it is what it was.",
+        );
+        assert_eq!(
+            summarize(StopWords::English),
+            "Rust compiles the code into fast binaries. The compiler checks the code of the binaries.",
+        );
+        assert_eq!(
+            summarize(StopWords::Custom(
+                stop_words::ENGLISH
+                    .iter()
+                    .map(|word| word.to_uppercase())
+                    .collect(),
+            )),
+            summarize(StopWords::English),
+        );
+    }
+
+    #[test]
+    fn test_summarize_falls_back_to_text() {
+        let text = "...";
+        for summarizer in [Summarizer::Naive, Summarizer::RankBased] {
+            assert_eq!(
+                summarize(&summarizer, &plain_text(text), &Config::default()).unwrap(),
+                text,
+            );
+        }
+    }
+}
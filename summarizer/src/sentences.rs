@@ -0,0 +1,134 @@
+// Copyright 2023 Xayn AG
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Lower cased abbreviations which are commonly followed by a capitalized word.
+#[rustfmt::skip]
+const ENGLISH_ABBREVIATIONS: &[&str] = &[
+    "dr", "e.g", "etc", "i.e", "inc", "jr", "ltd", "mr", "mrs", "ms", "no", "prof", "sr", "st",
+    "vs",
+];
+#[rustfmt::skip]
+const GERMAN_ABBREVIATIONS: &[&str] = &[
+    "bzw", "ca", "d.h", "dr", "fr", "hr", "nr", "prof", "str", "u.a", "usw", "vgl", "z.b",
+];
+
+/// Splits the text into trimmed sentences.
+///
+/// The boundaries follow the unicode sentence boundaries, which already keep decimals like
+/// `3.50` together. Additionally, sentences aren't split after single letters and the common
+/// abbreviations of the `language` (`"english"` or `"german"`, other languages only use the
+/// unicode boundaries).
+///
+/// # Examples
+///
+/// ```
+/// use xayn_summarizer::split_sentences;
+///
+/// assert_eq!(
+///     split_sentences("Dr. Smith paid $3.50. He left.", "english"),
+///     ["Dr. Smith paid $3.50.", "He left."],
+/// );
+/// ```
+pub fn split_sentences(text: &str, language: &str) -> Vec<String> {
+    let abbreviations = match language {
+        "english" | "en" => ENGLISH_ABBREVIATIONS,
+        "german" | "de" => GERMAN_ABBREVIATIONS,
+        _ => &[],
+    };
+
+    let mut sentences = Vec::<String>::new();
+    let mut merge_with_next = false;
+    for sentence in text.unicode_sentences() {
+        let sentence = sentence.trim();
+        if sentence.is_empty() {
+            continue;
+        }
+        match sentences.last_mut() {
+            Some(last) if merge_with_next => {
+                last.push(' ');
+                last.push_str(sentence);
+            }
+            _ => sentences.push(sentence.to_string()),
+        }
+        merge_with_next = ends_with_abbreviation(sentence, abbreviations);
+    }
+
+    sentences
+}
+
+fn ends_with_abbreviation(sentence: &str, abbreviations: &[&str]) -> bool {
+    let Some(word) = sentence
+        .strip_suffix('.')
+        .and_then(|sentence| sentence.split_whitespace().last())
+    else {
+        return false;
+    };
+
+    word.chars().count() == 1 || abbreviations.contains(&word.to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("Hello! How are you? Fine, thanks.", "english"),
+            ["Hello!", "How are you?", "Fine, thanks."],
+        );
+        assert_eq!(
+            split_sentences("  no punctuation at the end  ", "english"),
+            ["no punctuation at the end"],
+        );
+        assert!(split_sentences(" \n ", "english").is_empty());
+    }
+
+    #[test]
+    fn test_split_sentences_decimals_and_abbreviations() {
+        assert_eq!(
+            split_sentences("Dr. Smith paid $3.50. He left.", "english"),
+            ["Dr. Smith paid $3.50.", "He left."],
+        );
+        assert_eq!(
+            split_sentences(
+                "J. R. R. Tolkien wrote it in 1937. It sold well.",
+                "english"
+            ),
+            ["J. R. R. Tolkien wrote it in 1937.", "It sold well."],
+        );
+        assert_eq!(
+            split_sentences(
+                "Er traf Prof. Müller um ca. 3.15 Uhr. Dann ging er.",
+                "german"
+            ),
+            ["Er traf Prof. Müller um ca. 3.15 Uhr.", "Dann ging er."],
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_is_language_aware() {
+        let text = "Wir trafen Hr. Meier. Er war da.";
+        assert_eq!(
+            split_sentences(text, "german"),
+            ["Wir trafen Hr. Meier.", "Er war da."],
+        );
+        assert_eq!(
+            split_sentences(text, "english"),
+            ["Wir trafen Hr.", "Meier.", "Er war da."],
+        );
+    }
+}
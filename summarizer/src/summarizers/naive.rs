@@ -17,6 +17,8 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use crate::split_sentences;
+
 #[derive(Debug, PartialEq, Clone)]
 struct Sentence {
     index: usize,
@@ -36,9 +38,9 @@ struct Summarizer<'a> {
     _marker: std::marker::PhantomData<&'a str>,
 }
 
-pub(crate) fn summarize(text: &str, num_sentences: usize) -> String {
+pub(crate) fn summarize(text: &str, language: &str, num_sentences: usize) -> String {
     let bias_strength = Some(2.0);
-    let mut summariser = Summarizer::from_raw_text(text, ".", 50, 1500, false, bias_strength);
+    let mut summariser = Summarizer::from_raw_text(text, language, 50, 1500, false, bias_strength);
     let mut summary = summariser.top_sentences(
         num_sentences,
         false,
@@ -62,7 +64,7 @@ pub(crate) fn summarize(text: &str, num_sentences: usize) -> String {
         .map(|s| s.text)
         .fold(String::new(), |mut acc, it| {
             acc.push_str(it.as_str());
-            acc.push_str(".\n");
+            acc.push('\n');
             acc
         })
 }
@@ -70,14 +72,14 @@ pub(crate) fn summarize(text: &str, num_sentences: usize) -> String {
 impl<'a> Summarizer<'a> {
     fn from_raw_text(
         raw_text: &str,
-        separator: &str,
+        language: &str,
         min_length: usize,
         max_length: usize,
         ngrams: bool,
         bias_strength: Option<f32>,
     ) -> Summarizer<'a> {
         let sentences = Arc::new(Mutex::new(HashMap::new()));
-        let all_sentences = raw_text.split(separator).collect::<Vec<&str>>();
+        let all_sentences = split_sentences(raw_text, language);
 
         for (i, sentence) in all_sentences.iter().enumerate() {
             if sentence.len() > min_length && sentence.len() < max_length {
@@ -104,7 +106,7 @@ impl<'a> Summarizer<'a> {
                     index: i,
                     length: sentence.len(),
                     outgoing_connections: Some(outgoing_connections),
-                    text: (*sentence).to_string(),
+                    text: sentence.clone(),
                     number_of_connections: 0.0,
                 };
                 sentences.lock().unwrap().insert(i, sentence.clone());
@@ -17,13 +17,20 @@ use std::{cmp::min, collections::BTreeSet};
 use ndarray::{Array1, Array2};
 use unicode_segmentation::UnicodeSegmentation;
 
-pub(crate) fn summarize(text: &str, stop_words: &[&str], num_sentence: usize) -> String {
-    let sentences = text.unicode_sentences().collect::<Vec<&str>>();
+use crate::split_sentences;
+
+pub(crate) fn summarize(
+    text: &str,
+    language: &str,
+    stop_words: &[&str],
+    num_sentence: usize,
+) -> String {
+    let sentences = split_sentences(text, language);
     if num_sentence >= sentences.len() {
         return text.to_string();
     }
     let mut sentences_and_words = vec![];
-    for &sentence in &sentences {
+    for sentence in &sentences {
         let words = split_into_words(sentence);
         sentences_and_words.push(words);
     }
@@ -38,13 +45,13 @@ pub(crate) fn summarize(text: &str, stop_words: &[&str], num_sentence: usize) ->
     for i in 0..sentences.len() {
         if ranks[i] >= least_rank {
             included_count += 1;
-            result.push(sentences[i]);
+            result.push(sentences[i].as_str());
         }
         if included_count == num_sentence {
             break;
         }
     }
-    result.join("")
+    result.join(" ")
 }
 
 fn get_all_words_lc<'a>(sentence1: &[&'a str], sentence2: &[&'a str]) -> BTreeSet<String> {
@@ -99,6 +106,10 @@ fn sentence_similarity(s1: &[&str], s2: &[&str], stop_words: &[&str]) -> f64 {
     let all_words = get_all_words_lc(s1, s2);
     let v1 = get_sentence_vector(s1, &all_words, stop_words);
     let v2 = get_sentence_vector(s2, &all_words, stop_words);
+    if v1.iter().all(|&count| count == 0) || v2.iter().all(|&count| count == 0) {
+        // Hint: sentences consisting only of stop words are not similar to any other sentence
+        return 0.0;
+    }
     1.0 - cosine_distance(&v1, &v2)
 }
 
@@ -135,7 +146,7 @@ fn build_similarity_matrix(sentences: &Vec<Vec<&str>>, stop_words: &[&str]) -> A
     }
     for i in 0..len {
         for j in 0..len {
-            if i == j {
+            if i == j || sum_column[j] <= 0.0 {
                 continue;
             }
             matrix[[i, j]] /= sum_column[j];
@@ -39,6 +39,61 @@ pub async fn create_tenant_index(
     Ok(())
 }
 
+/// Creates an index with the mapping of the index of the tenant, except for the embedding size.
+///
+/// The mapping includes all indexed properties of the tenant.
+#[instrument(skip(elastic))]
+pub(crate) async fn create_index_like_tenant_index(
+    elastic: &ClientWithoutIndex,
+    tenant: &Tenant,
+    es_index_name: &str,
+    embedding_size: usize,
+) -> Result<(), Error> {
+    let Some(mapping) = get_opt_tenant_mapping(&elastic.with_index(&tenant.es_index_name)).await?
+    else {
+        bail!("index of the tenant doesn't exist");
+    };
+    let mapping = mapping_with_embedding_size(&mapping, embedding_size)?;
+    let elastic = elastic.with_index(es_index_name);
+    elastic
+        .query_with_json::<_, SerdeDiscard>(Method::PUT, elastic.create_url([], []), Some(&mapping))
+        .await?;
+    info!("created ES index");
+    Ok(())
+}
+
+/// Points the alias of the tenant to the index of the tenant.
+///
+/// The alias is removed from all other indices in the same atomic operation.
+#[instrument(skip(elastic))]
+pub async fn update_tenant_alias(
+    elastic: &ClientWithoutIndex,
+    tenant: &Tenant,
+) -> Result<(), Error> {
+    let elastic = elastic.with_index("_aliases");
+    elastic
+        .query_with_json::<_, SerdeDiscard>(
+            Method::POST,
+            elastic.create_url([], []),
+            Some(json!({
+                "actions": [
+                    { "remove": {
+                        "index": "*",
+                        "alias": &tenant.es_alias_name,
+                        "must_exist": false
+                    } },
+                    { "add": {
+                        "index": &tenant.es_index_name,
+                        "alias": &tenant.es_alias_name
+                    } }
+                ]
+            })),
+        )
+        .await?;
+    info!("updated ES alias");
+    Ok(())
+}
+
 #[instrument(skip(elastic))]
 pub async fn delete_index(elastic: &ClientWithoutIndex, index_name: &str) -> Result<(), Error> {
     let elastic = elastic.with_index(index_name);
@@ -67,6 +122,7 @@ pub(crate) async fn migrate_tenant_index(
         );
         create_tenant_index(elastic, tenant, embedding_size).await?;
     }
+    update_tenant_alias(elastic, tenant).await?;
 
     migrator
         .run_migration_if_needed("migrate_parent_property", async move {
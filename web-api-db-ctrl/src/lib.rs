@@ -22,13 +22,16 @@ pub mod postgres;
 mod postgres;
 pub mod tenant;
 
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
 use anyhow::{anyhow, bail};
 pub use elastic::create_tenant_index as elastic_create_tenant;
 use serde::{Deserialize, Serialize};
-use sqlx::pool::PoolOptions;
 use tenant::{Tenant, TenantWithOptionals};
+use tracing::warn;
 use xayn_web_api_shared::{
     elastic::{ClientWithoutIndex as EsClient, Config as EsConfig},
     postgres::{Client as PgClient, Config as PgConfig},
@@ -38,6 +41,9 @@ use xayn_web_api_shared::{
 //TODO
 pub type Error = anyhow::Error;
 
+/// The default time soft deleted tenants can be restored before they are purged.
+pub const DEFAULT_DELETED_TENANT_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 #[derive(Clone, Debug)]
 pub struct Silo {
     postgres_config: PgConfig,
@@ -46,6 +52,8 @@ pub struct Silo {
     elastic: EsClient,
     enable_legacy_tenant: Option<LegacyTenantInfo>,
     embedding_sizes: HashMap<String, usize>,
+    deleted_tenant_retention: Duration,
+    unavailable_models: HashSet<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -60,7 +68,8 @@ impl Silo {
         enable_legacy_tenant: Option<LegacyTenantInfo>,
         embedding_sizes: HashMap<String, usize>,
     ) -> Result<Self, Error> {
-        let postgres = PoolOptions::new()
+        let postgres = postgres_config
+            .to_pool_options()
             .connect_with(postgres_config.to_connection_options()?)
             .await?;
 
@@ -73,9 +82,25 @@ impl Silo {
             elastic,
             enable_legacy_tenant,
             embedding_sizes,
+            deleted_tenant_retention: DEFAULT_DELETED_TENANT_RETENTION,
+            unavailable_models: HashSet::new(),
         })
     }
 
+    /// Sets the time soft deleted tenants can be restored before they are purged.
+    pub fn with_deleted_tenant_retention(mut self, retention: Duration) -> Self {
+        self.deleted_tenant_retention = retention;
+        self
+    }
+
+    /// Sets the models which are configured but failed to load.
+    ///
+    /// The elastic indices of tenants using these models are not migrated on initialization.
+    pub fn with_unavailable_models(mut self, models: impl IntoIterator<Item = String>) -> Self {
+        self.unavailable_models = models.into_iter().collect();
+        self
+    }
+
     pub async fn initialize(&self) -> Result<Option<TenantId>, Error> {
         let opt_legacy_setup = self.enable_legacy_tenant.as_ref().map(move |legacy_info| {
             (
@@ -88,11 +113,19 @@ impl Silo {
                 },
                 move |tenant: Tenant| async move {
                     let embedding_size = self.embedding_size_for(&tenant)?;
-                    elastic::create_tenant_index(&self.elastic, &tenant, embedding_size).await
+                    elastic::create_tenant_index(&self.elastic, &tenant, embedding_size).await?;
+                    elastic::update_tenant_alias(&self.elastic, &tenant).await
                 },
             )
         });
-        let migrate_tenant = move |tenant, mut migrator| async move {
+        let migrate_tenant = move |tenant: Tenant, mut migrator| async move {
+            if self.unavailable_models.contains(&tenant.model) {
+                warn!(
+                    {%tenant.tenant_id, %tenant.model},
+                    "skipping index migration, the model of the tenant is unavailable"
+                );
+                return Ok(migrator);
+            }
             let embedding_size = self.embedding_size_for(&tenant)?;
             elastic::migrate_tenant_index(&self.elastic, &tenant, embedding_size, &mut migrator)
                 .await?;
@@ -110,6 +143,11 @@ impl Silo {
         postgres::list_tenants(&self.postgres).await
     }
 
+    /// Checks if the tenant exists, including soft deleted tenants.
+    pub async fn tenant_exists(&self, tenant_id: &TenantId) -> Result<bool, Error> {
+        postgres::tenant_exists(&self.postgres, tenant_id).await
+    }
+
     pub async fn create_tenant(&self, tenant: &Tenant) -> Result<(), Error> {
         let mut tx = self.postgres.begin().await?;
         postgres::create_tenant(&mut tx, tenant).await?;
@@ -117,6 +155,8 @@ impl Silo {
         let embedding_size = self.embedding_size_for(tenant)?;
         elastic::create_tenant_index(&self.elastic, tenant, embedding_size).await?;
         tx.commit().await?;
+        // a failure to create the alias is fixed during the next initialization
+        elastic::update_tenant_alias(&self.elastic, tenant).await?;
         Ok(())
     }
 
@@ -130,6 +170,85 @@ impl Silo {
         Ok(deleted_tenant)
     }
 
+    /// Marks the tenant as deleted without deleting any of its data.
+    ///
+    /// The tenant can't be used anymore, but it can be restored until the retention has expired
+    /// and it's purged. Returns `None` if the tenant doesn't exist or is already deleted.
+    pub async fn soft_delete_tenant(&self, tenant_id: TenantId) -> Result<Option<Tenant>, Error> {
+        let mut tx = self.postgres.begin().await?;
+        let deleted_tenant = postgres::soft_delete_tenant(&mut tx, tenant_id).await?;
+        tx.commit().await?;
+        Ok(deleted_tenant)
+    }
+
+    /// Restores a soft deleted tenant whose retention hasn't expired yet.
+    pub async fn restore_tenant(&self, tenant_id: TenantId) -> Result<Option<Tenant>, Error> {
+        let mut tx = self.postgres.begin().await?;
+        let restored_tenant =
+            postgres::restore_tenant(&mut tx, tenant_id, self.deleted_tenant_retention).await?;
+        tx.commit().await?;
+        Ok(restored_tenant)
+    }
+
+    /// Enables or disables the ingestion of documents for the tenant.
+    ///
+    /// Everything else of the tenant, e.g. the search, is unaffected. Returns `None` if the tenant
+    /// doesn't exist or is deleted.
+    pub async fn set_ingestion_enabled(
+        &self,
+        tenant_id: TenantId,
+        enabled: bool,
+    ) -> Result<Option<Tenant>, Error> {
+        let mut tx = self.postgres.begin().await?;
+        let tenant = postgres::set_ingestion_enabled(&mut tx, tenant_id, enabled).await?;
+        tx.commit().await?;
+        Ok(tenant)
+    }
+
+    /// Deletes all soft deleted tenants whose retention has expired.
+    ///
+    /// Each tenant is purged on its own and a failure doesn't stop the purging of the other
+    /// tenants. The deletion of a tenant in postgres is committed before its index is deleted, so
+    /// that a failure never leaves a tenant behind which points to a missing index.
+    pub async fn purge_expired_tenants(&self) -> Result<PurgedTenants, Error> {
+        let expired_tenants =
+            postgres::list_expired_tenants(&self.postgres, self.deleted_tenant_retention).await?;
+        let mut purged = PurgedTenants::default();
+        for tenant_id in expired_tenants {
+            match self.purge_expired_tenant(&tenant_id).await {
+                Ok(Some(tenant)) => purged.tenants.push(tenant),
+                Ok(None) => {}
+                Err(err) => purged.failures.push(PurgeFailure {
+                    tenant_id,
+                    msg: err.to_string(),
+                }),
+            }
+        }
+        Ok(purged)
+    }
+
+    async fn purge_expired_tenant(&self, tenant_id: &TenantId) -> Result<Option<Tenant>, Error> {
+        let mut tx = self.postgres.begin().await?;
+        if !postgres::lock_expired_tenant(&mut tx, tenant_id, self.deleted_tenant_retention).await?
+        {
+            // restored or purged concurrently
+            return Ok(None);
+        }
+        let Some(tenant) = postgres::delete_tenant(&mut tx, tenant_id.clone()).await? else {
+            return Ok(None);
+        };
+        tx.commit().await?;
+        elastic::delete_index(&self.elastic, &tenant.es_index_name)
+            .await
+            .map_err(|err| {
+                anyhow!(
+                    "deleted the tenant, but failed to delete its index {}: {err}",
+                    tenant.es_index_name,
+                )
+            })?;
+        Ok(Some(tenant))
+    }
+
     pub async fn change_es_index(
         &self,
         tenant_id: &TenantId,
@@ -141,10 +260,65 @@ impl Silo {
 
         let mut tx = self.postgres.begin().await?;
         postgres::change_es_index(&mut tx, tenant_id, new_index).await?;
+        let tenant = Tenant::load_from_postgres(&mut tx, tenant_id.clone()).await?;
         tx.commit().await?;
+        // a failure to move the alias is fixed during the next initialization
+        elastic::update_tenant_alias(&self.elastic, &tenant).await?;
+        Ok(())
+    }
+
+    /// Creates the index into which the documents of the tenant are re-embedded with the model.
+    ///
+    /// The index has the mapping of the index of the tenant, but the embedding size of the model.
+    /// Nothing happens if the index already exists.
+    pub async fn create_reembedding_index(
+        &self,
+        tenant: &Tenant,
+        model: &str,
+        es_index_name: &str,
+    ) -> Result<(), Error> {
+        let embedding_size = self.embedding_size_of_model(model)?;
+        if !elastic::does_index_exist(&self.elastic, es_index_name).await? {
+            elastic::create_index_like_tenant_index(
+                &self.elastic,
+                tenant,
+                es_index_name,
+                embedding_size,
+            )
+            .await?;
+        }
         Ok(())
     }
 
+    /// Switches the tenant to the model and the index into which its documents were re-embedded.
+    ///
+    /// The alias of the tenant is moved to the new index and the previous index of the tenant is
+    /// deleted. Returns the updated tenant.
+    pub async fn change_model(
+        &self,
+        tenant: &Tenant,
+        model: &str,
+        es_index_name: &str,
+    ) -> Result<Tenant, Error> {
+        self.embedding_size_of_model(model)?;
+        if !elastic::does_index_exist(&self.elastic, es_index_name).await? {
+            bail!("index the tenant is supposed to switch to doesn't exist");
+        }
+
+        let mut tx = self.postgres.begin().await?;
+        let updated_tenant =
+            postgres::change_model(&mut tx, tenant.tenant_id.clone(), model, es_index_name)
+                .await?
+                .ok_or_else(|| anyhow!("unknown tenant {}", tenant.tenant_id))?;
+        tx.commit().await?;
+        // a failure to move the alias is fixed during the next initialization
+        elastic::update_tenant_alias(&self.elastic, &updated_tenant).await?;
+        if tenant.es_index_name != updated_tenant.es_index_name {
+            elastic::delete_index(&self.elastic, &tenant.es_index_name).await?;
+        }
+        Ok(updated_tenant)
+    }
+
     pub async fn run_operations(
         &self,
         initialize: bool,
@@ -171,18 +345,57 @@ impl Silo {
                     msg: err.to_string(),
                 }),
             Operation::CreateTenant(tenant_with_optionals) => {
-                let tenant = tenant_with_optionals.into();
-                self.create_tenant(&tenant)
-                    .await
-                    .map(|()| OperationResult::CreateTenant { tenant })
-                    .unwrap_or_else(|err| OperationResult::Error {
-                        msg: err.to_string(),
-                    })
+                let tenant = Tenant::from(tenant_with_optionals);
+                async {
+                    if self.tenant_exists(&tenant.tenant_id).await? {
+                        return Ok(OperationResult::TenantAlreadyExists {
+                            tenant_id: tenant.tenant_id,
+                        });
+                    }
+                    self.create_tenant(&tenant).await?;
+                    Ok(OperationResult::CreateTenant { tenant })
+                }
+                .await
+                .unwrap_or_else(|err: Error| OperationResult::Error {
+                    msg: err.to_string(),
+                })
+            }
+            Operation::DeleteTenant { tenant_id } => async {
+                if !self.tenant_exists(&tenant_id).await? {
+                    return Ok(OperationResult::TenantNotFound { tenant_id });
+                }
+                let tenant = self.delete_tenant(tenant_id).await?;
+                Ok(OperationResult::DeleteTenant { tenant })
             }
-            Operation::DeleteTenant { tenant_id } => self
-                .delete_tenant(tenant_id)
+            .await
+            .unwrap_or_else(|err: Error| OperationResult::Error {
+                msg: err.to_string(),
+            }),
+            Operation::SoftDeleteTenant { tenant_id } => self
+                .soft_delete_tenant(tenant_id)
+                .await
+                .map(|tenant| OperationResult::SoftDeleteTenant { tenant })
+                .unwrap_or_else(|err| OperationResult::Error {
+                    msg: err.to_string(),
+                }),
+            Operation::RestoreTenant { tenant_id } => self
+                .restore_tenant(tenant_id)
+                .await
+                .map(|tenant| OperationResult::RestoreTenant { tenant })
+                .unwrap_or_else(|err| OperationResult::Error {
+                    msg: err.to_string(),
+                }),
+            Operation::SetIngestionEnabled { tenant_id, enabled } => self
+                .set_ingestion_enabled(tenant_id, enabled)
                 .await
-                .map(|tenant| OperationResult::DeleteTenant { tenant })
+                .map(|tenant| OperationResult::SetIngestionEnabled { tenant })
+                .unwrap_or_else(|err| OperationResult::Error {
+                    msg: err.to_string(),
+                }),
+            Operation::PurgeExpiredTenants {} => self
+                .purge_expired_tenants()
+                .await
+                .map(|purged| OperationResult::PurgeExpiredTenants { purged })
                 .unwrap_or_else(|err| OperationResult::Error {
                     msg: err.to_string(),
                 }),
@@ -215,6 +428,13 @@ impl Silo {
         &self.elastic
     }
 
+    fn embedding_size_of_model(&self, model: &str) -> Result<usize, Error> {
+        self.embedding_sizes
+            .get(model)
+            .copied()
+            .ok_or_else(|| anyhow!("unknown model ({model})"))
+    }
+
     pub fn embedding_size_for(&self, tenant: &Tenant) -> Result<usize, Error> {
         self.embedding_sizes
             .get(&tenant.model)
@@ -240,6 +460,17 @@ pub enum Operation {
     DeleteTenant {
         tenant_id: TenantId,
     },
+    SoftDeleteTenant {
+        tenant_id: TenantId,
+    },
+    RestoreTenant {
+        tenant_id: TenantId,
+    },
+    SetIngestionEnabled {
+        tenant_id: TenantId,
+        enabled: bool,
+    },
+    PurgeExpiredTenants {},
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -247,6 +478,26 @@ pub enum OperationResult {
     ListTenants { tenants: Vec<Tenant> },
     CreateTenant { tenant: Tenant },
     DeleteTenant { tenant: Option<Tenant> },
+    SoftDeleteTenant { tenant: Option<Tenant> },
+    RestoreTenant { tenant: Option<Tenant> },
+    SetIngestionEnabled { tenant: Option<Tenant> },
+    PurgeExpiredTenants { purged: PurgedTenants },
+    TenantAlreadyExists { tenant_id: TenantId },
+    TenantNotFound { tenant_id: TenantId },
     Success,
     Error { msg: String },
 }
+
+/// The outcome of purging the expired soft deleted tenants.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PurgedTenants {
+    pub tenants: Vec<Tenant>,
+    pub failures: Vec<PurgeFailure>,
+}
+
+/// A soft deleted tenant which failed to be purged.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct PurgeFailure {
+    pub tenant_id: TenantId,
+    pub msg: String,
+}
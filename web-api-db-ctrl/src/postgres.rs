@@ -173,6 +173,7 @@ where
             is_legacy_tenant: true,
             es_index_name,
             model: None,
+            ingestion_enabled: None,
         }
         .into();
         create_tenant_role_and_schema(tx, &tenant, true).await?;
@@ -194,7 +195,8 @@ async fn run_all_db_migrations<F>(
 where
     F: Future<Output = Result<PgExternalMigrator, Error>>,
 {
-    let tenants = list_tenants(pool).await?;
+    // Hint: soft deleted tenants are migrated too, so that they can be restored
+    let tenants = query_tenants(pool, true).await?;
     // Hint: Parallelism is implicitly limited by the connection pool.
     let results = join_all(tenants.iter().map(|tenant| {
         let migrate_tenant = &migrate_tenant;
@@ -270,22 +272,61 @@ pub(super) async fn admin_as_mt_user_hack(pool: &Pool<Postgres>) -> Result<(), E
 
 #[instrument(skip(pool), err)]
 pub(super) async fn list_tenants(pool: &Pool<Postgres>) -> Result<Vec<Tenant>, Error> {
+    query_tenants(pool, false).await
+}
+
+/// Checks if the tenant exists, soft deleted tenants are considered as existing.
+#[instrument(skip(pool), err)]
+pub(super) async fn tenant_exists(
+    pool: &Pool<Postgres>,
+    tenant_id: &TenantId,
+) -> Result<bool, Error> {
+    let (exists,) = sqlx::query_as::<_, (bool,)>(
+        "SELECT EXISTS (SELECT FROM management.tenant WHERE tenant_id = $1);",
+    )
+    .bind(tenant_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(exists)
+}
+
+/// The columns of a tenant without its id.
+type TenantRow = (bool, Option<String>, Option<String>, bool);
+
+fn tenant_from_row(
+    tenant_id: TenantId,
+    (is_legacy_tenant, es_index_name, model, ingestion_enabled): TenantRow,
+) -> Tenant {
+    TenantWithOptionals {
+        tenant_id,
+        is_legacy_tenant,
+        es_index_name,
+        model,
+        ingestion_enabled: Some(ingestion_enabled),
+    }
+    .into()
+}
+
+async fn query_tenants(pool: &Pool<Postgres>, include_deleted: bool) -> Result<Vec<Tenant>, Error> {
     Ok(
-        sqlx::query_as::<_, (TenantId, bool, Option<String>, Option<String>)>(
-            "SELECT tenant_id, is_legacy_tenant, es_index_name, model FROM management.tenant",
+        sqlx::query_as::<_, (TenantId, bool, Option<String>, Option<String>, bool)>(
+            "SELECT tenant_id, is_legacy_tenant, es_index_name, model, ingestion_enabled
+            FROM management.tenant
+            WHERE $1 OR deleted_at IS NULL;",
         )
+        .bind(include_deleted)
         .fetch_all(pool)
         .await?
         .into_iter()
-        .map(|(tenant_id, is_legacy_tenant, es_index_name, model)| {
-            TenantWithOptionals {
-                tenant_id,
-                is_legacy_tenant,
-                es_index_name,
-                model,
-            }
-            .into()
-        })
+        .map(
+            |(tenant_id, is_legacy_tenant, es_index_name, model, ingestion_enabled)| {
+                tenant_from_row(
+                    tenant_id,
+                    (is_legacy_tenant, es_index_name, model, ingestion_enabled),
+                )
+            },
+        )
         .collect(),
     )
 }
@@ -297,23 +338,15 @@ pub(super) async fn delete_tenant(
 ) -> Result<Option<Tenant>, Error> {
     let tenant = QuotedIdentifier::db_name_for_tenant_id(&tenant_id);
 
-    let deleted_tenant = sqlx::query_as::<_, (bool, Option<String>, Option<String>)>(
+    let deleted_tenant = sqlx::query_as::<_, TenantRow>(
         "DELETE FROM management.tenant
            WHERE tenant_id = $1
-           RETURNING is_legacy_tenant, es_index_name, model;",
+           RETURNING is_legacy_tenant, es_index_name, model, ingestion_enabled;",
     )
     .bind(&tenant_id)
     .fetch_optional(&mut *tx)
     .await?
-    .map(|(is_legacy_tenant, es_index_name, model)| {
-        TenantWithOptionals {
-            tenant_id,
-            is_legacy_tenant,
-            es_index_name,
-            model,
-        }
-        .into()
-    });
+    .map(|row| tenant_from_row(tenant_id, row));
 
     if deleted_tenant.is_none() {
         return Ok(None);
@@ -332,6 +365,122 @@ pub(super) async fn delete_tenant(
     Ok(deleted_tenant)
 }
 
+#[instrument(skip(tx), err)]
+pub(super) async fn soft_delete_tenant(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: TenantId,
+) -> Result<Option<Tenant>, Error> {
+    Ok(sqlx::query_as::<_, TenantRow>(
+        "UPDATE management.tenant
+            SET deleted_at = now()
+            WHERE tenant_id = $1 AND deleted_at IS NULL
+            RETURNING is_legacy_tenant, es_index_name, model, ingestion_enabled;",
+    )
+    .bind(&tenant_id)
+    .fetch_optional(tx)
+    .await?
+    .map(|row| tenant_from_row(tenant_id, row)))
+}
+
+#[instrument(skip(tx), err)]
+pub(super) async fn restore_tenant(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: TenantId,
+    retention: Duration,
+) -> Result<Option<Tenant>, Error> {
+    Ok(sqlx::query_as::<_, TenantRow>(
+        "UPDATE management.tenant
+            SET deleted_at = NULL
+            WHERE tenant_id = $1 AND deleted_at > now() - make_interval(secs => $2)
+            RETURNING is_legacy_tenant, es_index_name, model, ingestion_enabled;",
+    )
+    .bind(&tenant_id)
+    .bind(retention.as_secs_f64())
+    .fetch_optional(tx)
+    .await?
+    .map(|row| tenant_from_row(tenant_id, row)))
+}
+
+/// Enables or disables the ingestion of a non deleted tenant.
+#[instrument(skip(tx), err)]
+pub(super) async fn set_ingestion_enabled(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: TenantId,
+    enabled: bool,
+) -> Result<Option<Tenant>, Error> {
+    Ok(sqlx::query_as::<_, TenantRow>(
+        "UPDATE management.tenant
+            SET ingestion_enabled = $2
+            WHERE tenant_id = $1 AND deleted_at IS NULL
+            RETURNING is_legacy_tenant, es_index_name, model, ingestion_enabled;",
+    )
+    .bind(&tenant_id)
+    .bind(enabled)
+    .fetch_optional(tx)
+    .await?
+    .map(|row| tenant_from_row(tenant_id, row)))
+}
+
+pub(super) async fn change_model(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: TenantId,
+    model: &str,
+    es_index_name: &str,
+) -> Result<Option<Tenant>, Error> {
+    Ok(sqlx::query_as::<_, TenantRow>(
+        "UPDATE management.tenant
+            SET model = $2, es_index_name = $3
+            WHERE tenant_id = $1 AND deleted_at IS NULL
+            RETURNING is_legacy_tenant, es_index_name, model, ingestion_enabled;",
+    )
+    .bind(&tenant_id)
+    .bind(model)
+    .bind(es_index_name)
+    .fetch_optional(tx)
+    .await?
+    .map(|row| tenant_from_row(tenant_id, row)))
+}
+
+/// Lists the ids of all soft deleted tenants whose retention has expired.
+#[instrument(skip(pool), err)]
+pub(super) async fn list_expired_tenants(
+    pool: &Pool<Postgres>,
+    retention: Duration,
+) -> Result<Vec<TenantId>, Error> {
+    Ok(sqlx::query_as::<_, (TenantId,)>(
+        "SELECT tenant_id
+        FROM management.tenant
+        WHERE deleted_at <= now() - make_interval(secs => $1);",
+    )
+    .bind(retention.as_secs_f64())
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|(tenant_id,)| tenant_id)
+    .collect())
+}
+
+/// Checks if the tenant is soft deleted and its retention has expired.
+///
+/// The tenant is locked until the end of the transaction, so that it can't be restored meanwhile.
+#[instrument(skip(tx), err)]
+pub(super) async fn lock_expired_tenant(
+    tx: &mut Transaction<'_, Postgres>,
+    tenant_id: &TenantId,
+    retention: Duration,
+) -> Result<bool, Error> {
+    Ok(sqlx::query(
+        "SELECT FROM management.tenant
+        WHERE tenant_id = $1 AND deleted_at <= now() - make_interval(secs => $2)
+        FOR UPDATE;",
+    )
+    .bind(tenant_id)
+    .bind(retention.as_secs_f64())
+    .fetch_optional(tx)
+    .await?
+    .is_some())
+}
+
 #[instrument(skip(tx), err)]
 pub(super) async fn create_tenant(
     tx: &mut Transaction<'_, Postgres>,
@@ -441,11 +590,12 @@ async fn create_tenant_role_and_schema(
         .try_for_each(|_| future::ready(Ok(())))
         .await?;
 
-    sqlx::query("INSERT INTO management.tenant (tenant_id, is_legacy_tenant, es_index_name, model) VALUES ($1, $2, $3, $4);")
+    sqlx::query("INSERT INTO management.tenant (tenant_id, is_legacy_tenant, es_index_name, model, ingestion_enabled) VALUES ($1, $2, $3, $4, $5);")
         .bind(&tenant_config.tenant_id)
         .bind(tenant_config.is_legacy_tenant)
         .bind(&tenant_config.es_index_name)
         .bind(&tenant_config.model)
+        .bind(tenant_config.ingestion_enabled)
         .execute(tx)
         .await?;
 
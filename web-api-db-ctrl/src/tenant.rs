@@ -25,7 +25,11 @@ pub struct Tenant {
     pub tenant_id: TenantId,
     pub is_legacy_tenant: bool,
     pub es_index_name: String,
+    /// The alias which points to the `es_index_name` and is used to access the index.
+    pub es_alias_name: String,
     pub model: String,
+    /// Whether documents can be ingested for the tenant.
+    pub ingestion_enabled: bool,
 }
 
 /// A helper struct which allows specifying `None` for all fields which have default values.
@@ -40,6 +44,8 @@ pub struct TenantWithOptionals {
     pub es_index_name: Option<String>,
     #[serde(default)]
     pub model: Option<String>,
+    #[serde(default)]
+    pub ingestion_enabled: Option<bool>,
 }
 
 impl From<TenantWithOptionals> for Tenant {
@@ -49,15 +55,22 @@ impl From<TenantWithOptionals> for Tenant {
             is_legacy_tenant,
             es_index_name,
             model,
+            ingestion_enabled,
         }: TenantWithOptionals,
     ) -> Self {
         let es_index_name = es_index_name.unwrap_or_else(|| tenant_id.to_string());
+        // `~` isn't allowed in tenant ids, hence the alias can't collide with the default index
+        // name of another tenant
+        let es_alias_name = format!("{tenant_id}~alias");
         let model = model.unwrap_or_else(|| "default".to_string());
+        let ingestion_enabled = ingestion_enabled.unwrap_or(true);
         Self {
             tenant_id,
             is_legacy_tenant,
             es_index_name,
+            es_alias_name,
             model,
+            ingestion_enabled,
         }
     }
 }
@@ -67,11 +80,11 @@ impl Tenant {
         connection: &mut PgConnection,
         tenant_id: TenantId,
     ) -> Result<Tenant, Error> {
-        let (is_legacy_tenant, es_index_name, model) =
-            sqlx::query_as::<_, (bool, Option<String>, Option<String>)>(
-                "SELECT is_legacy_tenant, es_index_name, model
+        let (is_legacy_tenant, es_index_name, model, ingestion_enabled) =
+            sqlx::query_as::<_, (bool, Option<String>, Option<String>, bool)>(
+                "SELECT is_legacy_tenant, es_index_name, model, ingestion_enabled
                 FROM management.tenant
-                WHERE tenant_id = $1;",
+                WHERE tenant_id = $1 AND deleted_at IS NULL;",
             )
             .bind(&tenant_id)
             .fetch_optional(connection)
@@ -83,6 +96,7 @@ impl Tenant {
             is_legacy_tenant,
             es_index_name,
             model,
+            ingestion_enabled: Some(ingestion_enabled),
         }
         .into())
     }
@@ -59,9 +59,13 @@ pub struct Config {
     pub password: Secret<String>,
     pub index_name: String,
 
-    /// Request timeout in seconds.
+    /// Connect timeout in seconds.
     #[serde(with = "serde_duration_as_seconds")]
-    pub timeout: Duration,
+    pub connect_timeout: Duration,
+
+    /// Request timeout in seconds, including the connect timeout.
+    #[serde(alias = "timeout", with = "serde_duration_as_seconds")]
+    pub request_timeout: Duration,
 
     /// The retry policy for internal requests to elastic search.
     pub retry_policy: ExponentialJitterRetryPolicyConfig,
@@ -76,7 +80,8 @@ impl Default for Config {
             user: "elastic".into(),
             password: String::from("changeme").into(),
             index_name: "test_index".into(),
-            timeout: Duration::from_secs(2),
+            connect_timeout: Duration::from_secs(2),
+            request_timeout: Duration::from_secs(2),
             retry_policy: ExponentialJitterRetryPolicyConfig {
                 max_retries: 3,
                 step_size: Duration::from_millis(300),
@@ -87,6 +92,15 @@ impl Default for Config {
     }
 }
 
+impl Config {
+    /// Sets both the connect and the request timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self.request_timeout = timeout;
+        self
+    }
+}
+
 #[derive(Debug)]
 struct Auth {
     user: String,
@@ -129,7 +143,8 @@ impl Client {
             user,
             password,
             index_name,
-            timeout,
+            connect_timeout,
+            request_timeout,
             retry_policy,
             default_request_per_second,
         } = config;
@@ -139,7 +154,10 @@ impl Client {
                 .parse::<SegmentableUrl>()?
                 .with_segments([&index_name])
                 .into(),
-            client: reqwest::ClientBuilder::new().timeout(timeout).build()?,
+            client: reqwest::ClientBuilder::new()
+                .connect_timeout(connect_timeout)
+                .timeout(request_timeout)
+                .build()?,
             retry_policy,
             default_request_per_second,
         })
@@ -657,3 +675,80 @@ impl FromStr for SegmentableUrl {
         s.parse::<Url>()?.try_into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        time::sleep,
+    };
+
+    use super::*;
+
+    /// Starts a server which answers a single request after the given delay.
+    async fn slow_server(delay: Duration) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0; 1024];
+            stream.read(&mut buffer).await.unwrap();
+            sleep(delay).await;
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\n{}")
+                .await
+                .ok(/* the client might have timed out already */);
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout() {
+        let client = Client::new(Config {
+            // non-routable address
+            url: "http://10.255.255.1:9200".into(),
+            connect_timeout: Duration::from_millis(100),
+            request_timeout: Duration::from_secs(10),
+            ..Config::default()
+        })
+        .unwrap();
+
+        let start = Instant::now();
+        let result = client.request(Method::GET, [], []).send().await;
+        assert!(result.is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let delay = Duration::from_millis(300);
+        let config = Config {
+            connect_timeout: Duration::from_millis(100),
+            request_timeout: Duration::from_secs(5),
+            ..Config::default()
+        };
+
+        let client = Client::new(Config {
+            url: slow_server(delay).await,
+            ..config.clone()
+        })
+        .unwrap();
+        let response = client.request(Method::GET, [], []).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let client = Client::new(Config {
+            url: slow_server(delay).await,
+            ..config.with_timeout(Duration::from_millis(100))
+        })
+        .unwrap();
+        let error = client
+            .request(Method::GET, [], [])
+            .send()
+            .await
+            .unwrap_err();
+        assert!(error.is_timeout());
+    }
+}
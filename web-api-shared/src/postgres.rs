@@ -12,16 +12,19 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr, time::Duration};
 
 use once_cell::sync::Lazy;
 use regex::Regex;
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgConnectOptions, Pool, Postgres, Type};
+use sqlx::{pool::PoolOptions, postgres::PgConnectOptions, Pool, Postgres, Type};
 use thiserror::Error;
 
-use crate::{request::TenantId, serde::serialize_redacted};
+use crate::{
+    request::TenantId,
+    serde::{serde_duration_as_seconds, serialize_redacted},
+};
 
 pub type Client = Pool<Postgres>;
 
@@ -61,6 +64,10 @@ pub struct Config {
 
     /// Maximum number of connections in the pool.
     pub max_pool_size: u8,
+
+    /// Maximum time in seconds to wait for a connection from the pool.
+    #[serde(with = "serde_duration_as_seconds")]
+    pub acquire_timeout: Duration,
 }
 
 impl Default for Config {
@@ -75,6 +82,7 @@ impl Default for Config {
             skip_migrations: false,
             min_pool_size: 0,
             max_pool_size: 25,
+            acquire_timeout: Duration::from_secs(30),
         }
     }
 }
@@ -110,6 +118,16 @@ impl Config {
 
         Ok(options)
     }
+
+    /// Creates pool options with the configured pool sizing.
+    ///
+    /// Defaults to at least 0 and at most 25 connections and a 30 seconds acquire timeout.
+    pub fn to_pool_options(&self) -> PoolOptions<Postgres> {
+        PoolOptions::new()
+            .min_connections(u32::from(self.min_pool_size))
+            .max_connections(u32::from(self.max_pool_size))
+            .acquire_timeout(self.acquire_timeout)
+    }
 }
 
 /// A quoted postgres identifier.
@@ -195,6 +213,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pool_options() {
+        let config = Config {
+            min_pool_size: 2,
+            max_pool_size: 3,
+            acquire_timeout: Duration::from_secs(4),
+            ..Config::default()
+        };
+        let options = config.to_pool_options();
+
+        assert_eq!(options.get_min_connections(), 2);
+        assert_eq!(options.get_max_connections(), 3);
+        assert_eq!(options.get_acquire_timeout(), Duration::from_secs(4));
+    }
+
     #[test]
     fn test_format_quoted_identifier() {
         assert_eq!(
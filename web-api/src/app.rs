@@ -22,7 +22,7 @@ use futures_util::FutureExt;
 use serde::{de::DeserializeOwned, Serialize};
 use tracing::{info, instrument};
 
-pub(crate) use self::state::{AppState, TenantState};
+pub(crate) use self::state::{ready, AppState, TenantState};
 use crate::{
     config::Config,
     extractor,
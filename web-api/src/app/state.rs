@@ -16,12 +16,14 @@ use std::sync::Arc;
 
 use actix_web::{
     dev::Payload,
-    web::{Data, ServiceConfig},
+    web::{Data, Json, ServiceConfig},
     FromRequest,
     HttpRequest,
+    Responder,
 };
 use derive_more::AsRef;
 use futures_util::{future::BoxFuture, FutureExt};
+use serde::Serialize;
 use xayn_ai_coi::CoiSystem;
 use xayn_snippet_extractor::pool::SnippetExtractorPool;
 use xayn_web_api_db_ctrl::Silo;
@@ -62,8 +64,13 @@ impl AppState {
     pub(super) async fn create(config: Config) -> Result<Self, SetupError> {
         let extractor = TextExtractor::new(config.as_ref())?;
         let models = Models::load(config.as_ref(), config.as_ref()).await?;
-        let (silo, legacy_tenant) =
-            initialize_silo(config.as_ref(), config.as_ref(), models.embedding_sizes()).await?;
+        let (silo, legacy_tenant) = initialize_silo(
+            config.as_ref(),
+            config.as_ref(),
+            models.embedding_sizes(),
+            models.unavailable(),
+        )
+        .await?;
         let storage_builder = Arc::new(Storage::builder(config.as_ref(), legacy_tenant).await?);
         let snippet_extractor = SnippetExtractorPool::new(config.as_ref())?;
         Ok(Self {
@@ -84,6 +91,27 @@ impl AppState {
     pub(crate) fn legacy_tenant(&self) -> Option<&TenantId> {
         self.storage_builder.legacy_tenant()
     }
+
+    pub(crate) fn storage_builder(&self) -> &StorageBuilder {
+        &self.storage_builder
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum Readiness {
+    Ready,
+    Degraded { unavailable_models: Vec<String> },
+}
+
+/// Reports if the service is ready or runs in a degraded mode because some models failed to load.
+pub(crate) async fn ready(state: Data<AppState>) -> impl Responder {
+    let unavailable_models = state.models.unavailable();
+    if unavailable_models.is_empty() {
+        Json(Readiness::Ready)
+    } else {
+        Json(Readiness::Degraded { unavailable_models })
+    }
 }
 
 /// Extract tenant specific state.
@@ -30,6 +30,20 @@ pub struct IngestionConfig {
     pub(crate) max_snippet_size: usize,
     pub(crate) max_properties_size: usize,
     pub(crate) max_properties_string_size: usize,
+    pub(crate) deduplication: DeduplicationMode,
+}
+
+/// How documents with the same content as an already ingested document are handled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum DeduplicationMode {
+    /// Ingest the document independent of its content.
+    #[default]
+    Disabled,
+    /// Skip the document if its content already exists under another id.
+    Skip,
+    /// Update the existing document in place if its content already exists under another id.
+    Update,
 }
 
 impl Default for IngestionConfig {
@@ -42,6 +56,7 @@ impl Default for IngestionConfig {
             max_snippet_size: 2_048,
             max_properties_size: 2_560,
             max_properties_string_size: 2_048,
+            deduplication: DeduplicationMode::default(),
         }
     }
 }
@@ -18,12 +18,12 @@ use displaydoc::Display;
 use futures_util::{stream::FuturesOrdered, TryStreamExt};
 use thiserror::Error;
 use xayn_snippet_extractor::pool::PooledSnippetExtractor;
-use xayn_summarizer::{self as summarizer, summarize, Source, Summarizer};
+use xayn_summarizer::{self as summarizer, summarize, Source, SummarizeError, Summarizer};
 
 use super::routes::InputData;
 use crate::{
     embedding::{Embedder, EmbeddingKind},
-    error::common::InvalidDocumentSnippet,
+    error::common::{InternalError, InvalidDocumentSnippet},
     extractor::TextExtractor,
     models::{DocumentContent, DocumentSnippet, PreprocessingStep},
     Error,
@@ -54,16 +54,47 @@ where
         InputData::Binary(binary) => text_extractor.extract_text(binary).await?,
     };
 
-    let res = match *preprocessing_step {
-        PreprocessingStep::None => embed_whole(embedder, kind, original).await,
+    match *preprocessing_step {
+        PreprocessingStep::None => embed_whole(embedder, kind, original)
+            .await
+            .map_err(PreprocessError::Fatal),
         PreprocessingStep::Summarize => embed_with_summarizer(embedder, kind, original).await,
         PreprocessingStep::CuttersSplit | PreprocessingStep::NltkSplitV1 => {
             *preprocessing_step = PreprocessingStep::NltkSplitV1;
-            embed_with_nltk(embedder, snippet_extractor, kind, original).await
+            embed_with_nltk(embedder, snippet_extractor, kind, original)
+                .await
+                .map_err(PreprocessError::Fatal)
         }
+    }
+}
+
+/// Replaces the embeddings of the already preprocessed snippets.
+///
+/// The snippets are embedded the same way as during their preprocessing, but the snippets
+/// themselves are left unchanged.
+pub(crate) async fn reembed(
+    embedder: &Embedder,
+    kind: EmbeddingKind,
+    preprocessing_step: PreprocessingStep,
+    snippets: &mut [DocumentContent],
+) -> Result<(), Error> {
+    let sequences: Vec<_> = if preprocessing_step == PreprocessingStep::Summarize {
+        snippets
+            .iter()
+            .map(|content| summarize_snippet(&content.snippet).map_err(InternalError::from_std))
+            .collect::<Result<_, _>>()?
+    } else {
+        snippets
+            .iter()
+            .map(|content| content.snippet.to_string())
+            .collect()
     };
+    let embeddings = embedder.run_batch(kind, sequences).await?;
+    for (content, embedding) in snippets.iter_mut().zip(embeddings) {
+        content.embedding = embedding;
+    }
 
-    res.map_err(PreprocessError::Fatal)
+    Ok(())
 }
 
 async fn embed_whole(
@@ -75,19 +106,33 @@ async fn embed_whole(
     Ok(vec![DocumentContent { snippet, embedding }])
 }
 
-async fn embed_with_summarizer(
-    embedder: &Embedder,
-    kind: EmbeddingKind,
-    snippet: DocumentSnippet,
-) -> Result<Vec<DocumentContent>, Error> {
-    let summary = summarize(
+fn summarize_snippet(snippet: &DocumentSnippet) -> Result<String, SummarizeError> {
+    summarize(
         &Summarizer::Naive,
         &Source::PlainText {
             text: snippet.to_string(),
         },
         &summarizer::Config::default(),
-    );
-    let embedding = embedder.run(kind, &summary).await?;
+    )
+}
+
+async fn embed_with_summarizer(
+    embedder: &Embedder,
+    kind: EmbeddingKind,
+    snippet: DocumentSnippet,
+) -> Result<Vec<DocumentContent>, PreprocessError> {
+    let summary = summarize_snippet(&snippet).map_err(|error| match error {
+        SummarizeError::EmptyInput => {
+            PreprocessError::Invalid(InvalidDocumentSnippet::NoSnippets {}.into())
+        }
+        SummarizeError::Fetch(_) | SummarizeError::Readability(_) => {
+            PreprocessError::Fatal(InternalError::from_std(error).into())
+        }
+    })?;
+    let embedding = embedder
+        .run(kind, &summary)
+        .await
+        .map_err(PreprocessError::Fatal)?;
     Ok(vec![DocumentContent {
         // Hint: Yes we do not use the summary, this is so that keyword/text search
         //       can use the original text.
@@ -12,15 +12,21 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::{collections::HashMap, matches};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    matches,
+    mem,
+};
 
 use actix_web::{
-    web::{self, Data, Json, Path, ServiceConfig},
+    http::header::{ETag, EntityTag, IfNoneMatch},
+    web::{self, Data, Header, Json, Path, ServiceConfig},
     HttpResponse,
     Responder,
 };
 use anyhow::anyhow;
 use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
 use futures_util::{
     stream::{FuturesOrdered, StreamExt},
     TryFutureExt,
@@ -32,12 +38,13 @@ use serde_json::{json, Value};
 use tokio::time::Instant;
 use tracing::{debug, error, info, instrument};
 use xayn_web_api_db_ctrl::{Operation, Silo};
+use xayn_web_api_shared::request::TenantId;
 
 use super::preprocessor::PreprocessError;
 use crate::{
     app::{AppState, TenantState},
     backoffice,
-    backoffice::IngestionConfig,
+    backoffice::{DeduplicationMode, IngestionConfig},
     embedding::EmbeddingKind,
     error::common::{
         BadRequest,
@@ -46,10 +53,15 @@ use crate::{
         DocumentPropertyNotFound,
         FailedToDeleteSomeDocuments,
         FailedToIngestDocuments,
+        FailedToReembedSomeDocuments,
         FailedToSetSomeDocumentCandidates,
         FailedToValidateDocuments,
         FileUploadNotEnabled,
+        IngestionNotPaused,
+        IngestionPaused,
+        InternalError,
         InvalidDocumentSnippet,
+        ReembeddingInProgress,
     },
     models::{
         self,
@@ -59,10 +71,15 @@ use crate::{
         DocumentPropertyId,
         DocumentSnippet,
         DocumentTags,
+        ExcerptedDocument,
         PreprocessingStep,
         Sha256Hash,
     },
-    storage::{self, property_filter::IndexedPropertiesSchemaUpdate},
+    storage::{
+        self,
+        properties_schema::PropertiesJsonSchema,
+        property_filter::IndexedPropertiesSchemaUpdate,
+    },
     utils::deprecate,
     Error,
 };
@@ -96,7 +113,18 @@ pub(crate) fn configure_service(config: &mut ServiceConfig) {
                 .route(web::post().to(create_indexed_properties))
                 .route(web::get().to(get_indexed_properties_schema)),
         )
-        .service(web::resource("/documents/{document_id}").route(web::delete().to(delete_document)))
+        .service(
+            web::resource("/documents/_properties_schema")
+                .route(web::get().to(get_properties_schema))
+                .route(web::put().to(put_properties_schema))
+                .route(web::delete().to(delete_properties_schema)),
+        )
+        .service(web::resource("/documents/_get").route(web::post().to(get_documents)))
+        .service(
+            web::resource("/documents/{document_id}")
+                .route(web::get().to(get_document))
+                .route(web::delete().to(delete_document)),
+        )
         .service(
             web::resource("/documents/{document_id}/properties")
                 .route(web::get().to(get_document_properties))
@@ -112,7 +140,9 @@ pub(crate) fn configure_service(config: &mut ServiceConfig) {
 }
 
 pub(crate) fn configure_ops_service(config: &mut ServiceConfig) {
-    config.service(web::resource("/silo_management").route(web::post().to(silo_management)));
+    config
+        .service(web::resource("/silo_management").route(web::post().to(silo_management)))
+        .service(web::resource("/reembedding").route(web::post().to(reembed_documents)));
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -188,6 +218,13 @@ impl InputData {
             InputData::Binary(binary) => binary,
         }
     }
+
+    fn content_sha256(&self) -> Sha256Hash {
+        match self {
+            InputData::Snippet(snippet) => Sha256Hash::calculate_normalized(snippet),
+            InputData::Binary(binary) => Sha256Hash::calculate(binary),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -195,6 +232,7 @@ struct InputDocument {
     id: DocumentId,
     original: InputData,
     original_sha256: Sha256Hash,
+    content_sha256: Sha256Hash,
     preprocessing_step: PreprocessingStep,
     properties: DocumentProperties,
     tags: DocumentTags,
@@ -246,7 +284,7 @@ impl NewIsCandidate {
 
 async fn validate_document_properties(
     properties: impl IntoIterator<Item = (String, Value)>,
-    storage: &(impl storage::Size + storage::IndexedProperties),
+    storage: &(impl storage::Size + storage::IndexedProperties + storage::PropertiesSchema),
     max_size: usize,
     max_property_string_size: usize,
 ) -> Result<DocumentProperties, Error> {
@@ -266,14 +304,20 @@ async fn validate_document_properties(
     }
 
     let size = storage::Size::json(storage, &serde_json::to_value(&properties)?).await?;
-    DocumentProperties::new(properties, size, max_size).map_err(Into::into)
+    let properties = DocumentProperties::new(properties, size, max_size)?;
+
+    if let Some(schema) = storage::PropertiesSchema::load(storage).await? {
+        schema.validate(&properties)?;
+    }
+
+    Ok(properties)
 }
 
 impl UnvalidatedDocumentForIngestion {
     async fn validate(
         self,
         config: &impl AsRef<IngestionConfig>,
-        storage: &(impl storage::Size + storage::IndexedProperties),
+        storage: &(impl storage::Size + storage::IndexedProperties + storage::PropertiesSchema),
     ) -> Result<InputDocument, Error> {
         let config = config.as_ref();
 
@@ -328,11 +372,13 @@ impl UnvalidatedDocumentForIngestion {
         };
 
         let original_sha256 = Sha256Hash::calculate(data.as_bytes());
+        let content_sha256 = data.content_sha256();
 
         Ok(InputDocument {
             id,
             original: data,
             original_sha256,
+            content_sha256,
             preprocessing_step,
             properties,
             tags,
@@ -348,16 +394,114 @@ struct IngestionRequestBody {
     documents: Vec<UnvalidatedDocumentForIngestion>,
 }
 
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum DeduplicationAction {
+    Skipped,
+    Updated,
+}
+
+#[derive(Debug, Serialize)]
+struct DeduplicatedDocument {
+    id: DocumentId,
+    duplicate_of: DocumentId,
+    action: DeduplicationAction,
+}
+
+/// Represents body of a POST documents response if deduplication is enabled.
+#[derive(Debug, Serialize)]
+struct IngestionResponseBody {
+    deduplicated_documents: Vec<DeduplicatedDocument>,
+}
+
+/// Skips or redirects the documents whose content already exists under another id.
+///
+/// Documents which share their content with an earlier document of the same batch are treated
+/// as duplicates of that document.
+///
+/// A duplicate is skipped instead of updating the existing document if that document is also part
+/// of the batch or if another duplicate of the batch already updates it. Hence each updated document
+/// stems from exactly one document of the request.
+async fn deduplicate_documents(
+    storage: &impl storage::ContentHash,
+    documents: &mut Vec<InputDocument>,
+    mode: DeduplicationMode,
+) -> Result<Vec<DeduplicatedDocument>, Error> {
+    let action = match mode {
+        DeduplicationMode::Disabled => return Ok(Vec::new()),
+        DeduplicationMode::Skip => DeduplicationAction::Skipped,
+        DeduplicationMode::Update => DeduplicationAction::Updated,
+    };
+
+    let hashes = documents
+        .iter()
+        .map(|document| document.content_sha256.clone())
+        .collect_vec();
+    let mut existing_ids = storage::ContentHash::get_ids(storage, &hashes).await?;
+    let request_ids = documents
+        .iter()
+        .map(|document| document.id.clone())
+        .collect::<HashSet<_>>();
+    let mut updated_ids = HashSet::new();
+
+    let mut deduplicated_documents = Vec::new();
+    documents.retain_mut(
+        |document| match existing_ids.entry(document.content_sha256.clone()) {
+            Entry::Occupied(entry) if entry.get() != &document.id => {
+                let duplicate_of = entry.get().clone();
+                info!(
+                    "Document '{}' is a duplicate of '{duplicate_of}'",
+                    document.id
+                );
+                let action = match action {
+                    DeduplicationAction::Updated
+                        if request_ids.contains(&duplicate_of)
+                            || !updated_ids.insert(duplicate_of.clone()) =>
+                    {
+                        DeduplicationAction::Skipped
+                    }
+                    action => action,
+                };
+                let id = match action {
+                    DeduplicationAction::Skipped => document.id.clone(),
+                    DeduplicationAction::Updated => {
+                        mem::replace(&mut document.id, duplicate_of.clone())
+                    }
+                };
+                deduplicated_documents.push(DeduplicatedDocument {
+                    id,
+                    duplicate_of,
+                    action,
+                });
+                matches!(action, DeduplicationAction::Updated)
+            }
+            Entry::Occupied(_) => true,
+            Entry::Vacant(entry) => {
+                entry.insert(document.id.clone());
+                true
+            }
+        },
+    );
+
+    Ok(deduplicated_documents)
+}
+
 #[instrument(skip_all)]
 async fn upsert_documents(
     state: Data<AppState>,
     Json(body): Json<IngestionRequestBody>,
     TenantState(storage, embedder): TenantState,
 ) -> Result<impl Responder, Error> {
+    if !storage.tenant().ingestion_enabled {
+        return Err(IngestionPaused.into());
+    }
+
     if body.documents.is_empty() {
-        return Ok(HttpResponse::NoContent());
+        return Ok(HttpResponse::NoContent().finish());
     }
 
+    embedder.ensure_available()?;
+
     if body.documents.len() > state.config.ingestion.max_document_batch_size {
         info!("{} documents exceeds maximum number", body.documents.len());
         return Err(BadRequest::from(format!(
@@ -385,6 +529,18 @@ async fn upsert_documents(
         }
     }
 
+    let deduplication = state.config.ingestion.deduplication;
+    let deduplicated_documents =
+        deduplicate_documents(&storage, &mut documents, deduplication).await?;
+    // Hint: failures are reported with the ids of the request, not the ids they were merged into,
+    //       which is unambiguous as each merged id stems from exactly one request document
+    let merged_ids = deduplicated_documents
+        .iter()
+        .filter(|document| matches!(document.action, DeduplicationAction::Updated))
+        .map(|document| (document.duplicate_of.to_string(), document.id.to_string()))
+        .collect::<HashMap<_, _>>();
+    let validated_len = invalid_documents.len();
+
     let ids = documents.iter().enumerate().fold(
         HashMap::with_capacity(documents.len()),
         |mut ids, (index, document)| {
@@ -489,13 +645,14 @@ async fn upsert_documents(
     let state = &state;
     let new_documents_len = new_documents.len();
 
-    let (new_documents, mut failed_documents, invalid_documents) = new_documents
+    let (new_documents, mut failed_documents, mut invalid_documents) = new_documents
         .into_iter()
         .map(|(mut document, new_is_candidate)| {
         let embedder = embedder.clone();
         async move {
             let id = document.id;
             let original_sha256 = Sha256Hash::calculate(document.original.as_bytes());
+            let content_sha256 = document.content_sha256;
 
             let result = backoffice::preprocessor::preprocess(
                 &embedder,
@@ -512,6 +669,7 @@ async fn upsert_documents(
                 Ok(snippets) => Ok(models::DocumentForIngestion {
                     id,
                     original_sha256,
+                    content_sha256,
                     snippets,
                     preprocessing_step: document.preprocessing_step,
                     properties: document.properties,
@@ -558,6 +716,15 @@ async fn upsert_documents(
             }),
     );
 
+    for document in failed_documents
+        .iter_mut()
+        .chain(&mut invalid_documents[validated_len..])
+    {
+        if let Some(id) = merged_ids.get(&document.id) {
+            document.id.clone_from(id);
+        }
+    }
+
     if !failed_documents.is_empty() {
         failed_documents.extend(invalid_documents);
         Err(FailedToIngestDocuments {
@@ -569,8 +736,12 @@ async fn upsert_documents(
             documents: invalid_documents,
         }
         .into())
+    } else if deduplication == DeduplicationMode::Disabled {
+        Ok(HttpResponse::Created().finish())
     } else {
-        Ok(HttpResponse::Created())
+        Ok(HttpResponse::Created().json(IngestionResponseBody {
+            deduplicated_documents,
+        }))
     }
 }
 
@@ -613,6 +784,98 @@ struct BatchDeleteRequest {
     documents: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchGetRequest {
+    documents: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct DocumentResponse {
+    id: DocumentId,
+    properties: DocumentProperties,
+    tags: DocumentTags,
+    is_candidate: bool,
+}
+
+impl From<ExcerptedDocument> for DocumentResponse {
+    fn from(document: ExcerptedDocument) -> Self {
+        Self {
+            id: document.id,
+            properties: document.properties,
+            tags: document.tags,
+            is_candidate: document.is_candidate,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct BatchGetResponse {
+    documents: Vec<Option<DocumentResponse>>,
+}
+
+/// Gets the document, honoring `If-None-Match` with the entity tag of the document.
+#[instrument(skip(if_none_match, storage))]
+async fn get_document(
+    document_id: Path<String>,
+    if_none_match: Option<Header<IfNoneMatch>>,
+    TenantState(storage, _): TenantState,
+) -> Result<HttpResponse, Error> {
+    let document_id = document_id.into_inner().try_into()?;
+    let document = storage::Document::get_excerpted(&storage, [&document_id])
+        .await?
+        .pop()
+        .ok_or(DocumentNotFound)?;
+
+    let entity_tag = EntityTag::new_strong(document.entity_tag());
+    let is_unchanged = if_none_match.map_or(false, |if_none_match| match if_none_match.0 {
+        IfNoneMatch::Any => true,
+        IfNoneMatch::Items(entity_tags) => {
+            entity_tags.iter().any(|other| other.weak_eq(&entity_tag))
+        }
+    });
+
+    if is_unchanged {
+        Ok(HttpResponse::NotModified()
+            .insert_header(ETag(entity_tag))
+            .finish())
+    } else {
+        Ok(HttpResponse::Ok()
+            .insert_header(ETag(entity_tag))
+            .json(DocumentResponse::from(document)))
+    }
+}
+
+/// Gets the documents in the order of the requested ids, with `null` for missing documents.
+#[instrument(skip_all)]
+async fn get_documents(
+    state: Data<AppState>,
+    Json(body): Json<BatchGetRequest>,
+    TenantState(storage, _): TenantState,
+) -> Result<impl Responder, Error> {
+    if body.documents.len() > state.config.ingestion.max_document_batch_size {
+        return Err(BadRequest::from(format!(
+            "Document batch size exceeded maximum of {}.",
+            state.config.ingestion.max_document_batch_size
+        ))
+        .into());
+    }
+
+    let ids = body
+        .documents
+        .into_iter()
+        .map(DocumentId::try_from)
+        .try_collect::<_, Vec<_>, _>()?;
+    let documents = storage::Document::get_excerpted(&storage, &ids)
+        .await?
+        .into_iter()
+        .map(|document| (document.id.clone(), DocumentResponse::from(document)))
+        .collect::<HashMap<_, _>>();
+    let documents = ids.iter().map(|id| documents.get(id).cloned()).collect();
+
+    Ok(Json(BatchGetResponse { documents }))
+}
+
 #[derive(Debug, Serialize)]
 struct DocumentCandidatesResponse {
     documents: Vec<DocumentId>,
@@ -711,6 +974,9 @@ async fn delete_document_properties(
     TenantState(storage, _): TenantState,
 ) -> Result<impl Responder, Error> {
     let document_id = document_id.into_inner().try_into()?;
+    if let Some(schema) = storage::PropertiesSchema::load(&storage).await? {
+        schema.validate(&DocumentProperties::default())?;
+    }
     storage::DocumentProperties::delete(&storage, &document_id)
         .await?
         .ok_or(DocumentNotFound)?;
@@ -791,6 +1057,15 @@ async fn delete_document_property(
     let (document_id, property_id) = ids.into_inner();
     let document_id = document_id.try_into()?;
     let property_id = property_id.try_into()?;
+    if let Some(schema) = storage::PropertiesSchema::load(&storage).await? {
+        let mut properties = storage::DocumentProperties::get(&storage, &document_id)
+            .await?
+            .ok_or(DocumentNotFound)?;
+        properties
+            .remove(&property_id)
+            .ok_or(DocumentPropertyNotFound)?;
+        schema.validate(&properties)?;
+    }
     storage::DocumentProperty::delete(&storage, &document_id, &property_id)
         .await?
         .ok_or(DocumentNotFound)?
@@ -819,6 +1094,167 @@ async fn get_indexed_properties_schema(
         .map(Json)
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct PropertiesSchemaBody {
+    schema: Option<PropertiesJsonSchema>,
+}
+
+#[instrument(skip(storage))]
+async fn get_properties_schema(
+    TenantState(storage, _): TenantState,
+) -> Result<impl Responder, Error> {
+    let schema = storage::PropertiesSchema::load(&storage).await?;
+
+    Ok(Json(PropertiesSchemaBody { schema }))
+}
+
+#[instrument(skip(body, storage))]
+async fn put_properties_schema(
+    Json(body): Json<PropertiesSchemaBody>,
+    TenantState(storage, _): TenantState,
+) -> Result<impl Responder, Error> {
+    storage::PropertiesSchema::store(&storage, body.schema.as_ref()).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[instrument(skip(storage))]
+async fn delete_properties_schema(
+    TenantState(storage, _): TenantState,
+) -> Result<impl Responder, Error> {
+    storage::PropertiesSchema::store(&storage, None).await?;
+
+    Ok(HttpResponse::NoContent())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReembeddingRequest {
+    tenant_id: TenantId,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    count: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReembeddingResponse {
+    model: String,
+    processed: usize,
+    total: usize,
+    finished: bool,
+}
+
+/// Embeds the documents of a tenant again into a new index and switches the tenant to it.
+///
+/// The model defaults to the current model of the tenant. The documents are processed in batches
+/// in the order of their ids and the progress is stored per tenant after each batch, hence a
+/// re-embedding continues where it was interrupted. If a count is given, then at most that many
+/// documents are processed per request.
+///
+/// The ingestion of the tenant must be paused until the re-embedding is finished. Other changes
+/// to the documents in the meantime aren't reflected in the documents which are already
+/// re-embedded.
+#[instrument(skip(state, silo))]
+async fn reembed_documents(
+    state: Data<AppState>,
+    silo: Data<Silo>,
+    Json(body): Json<ReembeddingRequest>,
+) -> Result<impl Responder, Error> {
+    if body.count == Some(0) {
+        return Err(BadRequest::from("count must be at least 1").into());
+    }
+    let storage = state.storage_builder().build_for(body.tenant_id).await?;
+    let tenant = storage.tenant();
+    if tenant.ingestion_enabled {
+        return Err(IngestionNotPaused.into());
+    }
+    let model = body.model.as_ref().unwrap_or(&tenant.model);
+    if state.models.get(model).is_none() {
+        return Err(BadRequest::from(format!("unknown model: {model}")).into());
+    }
+
+    // `~` isn't allowed in tenant ids, hence the index can't collide with another tenant
+    let es_index_name = format!(
+        "{}~{}",
+        tenant.tenant_id,
+        Utc::now().format("%Y%m%d%H%M%S%3f")
+    );
+    let mut reembedding = storage::Reembedding::start(&storage, model, &es_index_name).await?;
+    if body.model.is_some() && reembedding.model != *model {
+        return Err(ReembeddingInProgress {
+            model: reembedding.model,
+        }
+        .into());
+    }
+    let embedder = state.models.get(&reembedding.model).ok_or_else(|| {
+        InternalError::from_message(format!(
+            "deployment doesn't support the model of the re-embedding: {}",
+            reembedding.model,
+        ))
+    })?;
+    embedder.ensure_available()?;
+    silo.create_reembedding_index(tenant, &reembedding.model, &reembedding.es_index_name)
+        .await?;
+
+    let batch_size = state.config.ingestion.max_document_batch_size;
+    let mut remaining = body.count.unwrap_or(usize::MAX);
+    while remaining > 0 {
+        let mut documents = storage::Reembedding::get(
+            &storage,
+            reembedding.last_document_id.as_ref(),
+            batch_size.min(remaining),
+        )
+        .await?;
+        if documents.is_empty() {
+            break;
+        }
+        for document in &mut documents {
+            backoffice::preprocessor::reembed(
+                embedder,
+                EmbeddingKind::Content,
+                document.preprocessing_step,
+                &mut document.snippets,
+            )
+            .await?;
+        }
+
+        let failed_documents =
+            storage::Reembedding::update(&storage, &reembedding, &documents).await?;
+        if !failed_documents.is_empty() {
+            return Err(FailedToReembedSomeDocuments {
+                documents: failed_documents.into_iter().map(Into::into).collect(),
+            }
+            .into());
+        }
+
+        remaining -= documents.len();
+        reembedding.last_document_id = documents.pop().map(|document| document.id);
+    }
+
+    let (processed, total) =
+        storage::Reembedding::progress(&storage, reembedding.last_document_id.as_ref()).await?;
+    let finished = processed == total;
+    if finished {
+        // the tenant is switched first, so that a failure to finish is repaired by a retry
+        silo.change_model(tenant, &reembedding.model, &reembedding.es_index_name)
+            .await?;
+        storage::Reembedding::finish(&storage).await?;
+        info!(
+            "Tenant '{}' is re-embedded with model '{}'",
+            tenant.tenant_id, reembedding.model,
+        );
+    }
+
+    Ok(Json(ReembeddingResponse {
+        model: reembedding.model,
+        processed,
+        total,
+        finished,
+    }))
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 struct ManagementRequest {
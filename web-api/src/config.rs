@@ -32,7 +32,7 @@ use crate::{
     backoffice::IngestionConfig,
     embedding,
     extractor,
-    frontoffice::{PersonalizationConfig, SemanticSearchConfig},
+    frontoffice::{PersonalizationConfig, SemanticSearchConfig, TextEmbeddingConfig},
     logging,
     net,
     storage::{self},
@@ -54,6 +54,7 @@ pub struct Config {
     pub(crate) text_extractor: extractor::Config,
     pub(crate) personalization: PersonalizationConfig,
     pub(crate) semantic_search: SemanticSearchConfig,
+    pub(crate) text_embedding: TextEmbeddingConfig,
     pub(crate) ingestion: IngestionConfig,
     pub(crate) snippet_extractor: xayn_snippet_extractor::Config,
     pub(crate) tenants: tenants::Config,
@@ -105,6 +106,7 @@ impl UnvalidatedConfig {
         config.ingestion.validate()?;
         config.personalization.validate()?;
         config.semantic_search.validate()?;
+        config.text_embedding.validate()?;
 
         if config.models.is_empty() && config.embedding.is_none() {
             warn!("using default fallback for model config, models/embedders should be defined explicitly");
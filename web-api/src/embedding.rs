@@ -16,15 +16,27 @@ use std::{collections::HashMap, sync::Arc};
 
 use anyhow::bail;
 use aws_config::retry::RetryConfig;
-use aws_sdk_sagemakerruntime::{config::Region, primitives::Blob};
+use aws_sdk_sagemakerruntime::{
+    config::Region,
+    error::SdkError,
+    operation::invoke_endpoint::InvokeEndpointError,
+    primitives::Blob,
+};
+use itertools::Itertools;
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use tracing::error;
 use url::Url;
 use xayn_ai_bert::{AvgEmbedder, Config as EmbedderConfig, Embedding1, NormalizedEmbedding};
 use xayn_web_api_shared::serde::serialize_redacted;
 
-use crate::{app::SetupError, error::common::InternalError, utils::RelativePathBuf};
+use crate::{
+    app::SetupError,
+    error::common::{EmbedderUnavailable, InternalError, UpstreamUnavailable},
+    utils::RelativePathBuf,
+    Error,
+};
 
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(transparent)]
@@ -119,12 +131,21 @@ pub struct Sagemaker {
     pub(crate) retry_max_attempts: Option<u32>,
     pub(crate) aws_region: Option<String>,
     pub(crate) aws_profile: Option<String>,
+    pub(crate) max_batch_size: Option<usize>,
     #[serde(default)]
     pub(crate) prefix: Prefix,
 }
 
 impl Sagemaker {
+    /// The maximum number of sequences embedded by a single request if not configured otherwise.
+    const DEFAULT_MAX_BATCH_SIZE: usize = 16;
+
     async fn load(&self) -> Result<Embedder, SetupError> {
+        let max_batch_size = self.max_batch_size.unwrap_or(Self::DEFAULT_MAX_BATCH_SIZE);
+        if max_batch_size == 0 {
+            bail!("sagemaker max_batch_size must be at least 1");
+        }
+
         let mut config_loader = aws_config::from_env();
 
         if let Some(region) = &self.aws_region {
@@ -149,6 +170,7 @@ impl Sagemaker {
                 embedding_size: self.embedding_size,
                 endpoint: self.endpoint.clone(),
                 target_model: self.target_model.clone(),
+                max_batch_size,
             },
         })
     }
@@ -208,13 +230,30 @@ impl Models {
             bail!("model \"default\" is declared twice once explicit in \"models\" and once implicit through the \"embedding\" config");
         }
         let mut embedders = HashMap::new();
-        if let Some(default) = inject_default.as_ref() {
-            let embedder = Embedder::load(default).await?;
-            embedders.insert("default".to_owned(), Arc::new(embedder));
-        };
-        for (name, config) in &config.0 {
-            let embedder = Embedder::load(config).await?;
-            embedders.insert(name.clone(), Arc::new(embedder));
+        for (name, config) in inject_default
+            .iter()
+            .map(|config| ("default", config))
+            .chain(
+                config
+                    .0
+                    .iter()
+                    .map(|(name, config)| (name.as_str(), config)),
+            )
+        {
+            let embedder = match Embedder::load(config).await {
+                Ok(embedder) => embedder,
+                // a broken model shouldn't prevent the deployment from serving everything else
+                Err(error) if matches!(config, Config::Pipeline(_)) => {
+                    error!(
+                        model = name,
+                        %error,
+                        "failed to load model, starting in degraded mode",
+                    );
+                    Embedder::unavailable(name)
+                }
+                Err(error) => return Err(error),
+            };
+            embedders.insert(name.to_owned(), Arc::new(embedder));
         }
         Ok(Self(Arc::new(embedders)))
     }
@@ -223,10 +262,21 @@ impl Models {
         self.0.get(name)
     }
 
+    /// Gets the embedding sizes of all available models.
     pub(crate) fn embedding_sizes(&self) -> HashMap<String, usize> {
         self.0
             .iter()
-            .map(|(name, embedder)| (name.clone(), embedder.embedding_size()))
+            .filter_map(|(name, embedder)| Some((name.clone(), embedder.embedding_size()?)))
+            .collect()
+    }
+
+    /// Gets the names of all models which failed to load.
+    pub(crate) fn unavailable(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .filter(|(_, embedder)| !embedder.is_available())
+            .map(|(name, _)| name.clone())
+            .sorted_unstable()
             .collect()
     }
 }
@@ -243,17 +293,21 @@ enum InnerEmbedder {
         endpoint: String,
         embedding_size: usize,
         target_model: Option<String>,
+        max_batch_size: usize,
     },
     OpenAi {
         client: reqwest::Client,
         url: Url,
         embedding_size: usize,
     },
+    Unavailable {
+        model: String,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 struct SagemakerResponse {
-    embeddings: Vec<NormalizedEmbedding>,
+    embeddings: Vec<Vec<f32>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -263,7 +317,7 @@ struct OpenAiResponse {
 
 #[derive(Debug, Deserialize)]
 struct OpenAiResponseData {
-    embedding: Embedding1,
+    embedding: Vec<f32>,
 }
 
 #[derive(Copy, Clone)]
@@ -281,11 +335,45 @@ impl Embedder {
         }
     }
 
+    fn unavailable(model: &str) -> Self {
+        Self {
+            prefix: Prefix::default(),
+            inner: InnerEmbedder::Unavailable {
+                model: model.to_owned(),
+            },
+        }
+    }
+
+    pub(crate) fn is_available(&self) -> bool {
+        !matches!(self.inner, InnerEmbedder::Unavailable { .. })
+    }
+
+    /// Fails if the model failed to load at startup.
+    pub(crate) fn ensure_available(&self) -> Result<(), EmbedderUnavailable> {
+        if let InnerEmbedder::Unavailable { model } = &self.inner {
+            Err(EmbedderUnavailable {
+                model: model.clone(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
     pub(crate) async fn run(
         &self,
         kind: EmbeddingKind,
         sequence: &str,
-    ) -> Result<NormalizedEmbedding, InternalError> {
+    ) -> Result<NormalizedEmbedding, Error> {
+        let mut embeddings = self.run_batch(kind, [sequence]).await?;
+        Ok(embeddings.pop().unwrap(/* safe because run_batch returns one embedding per sequence */))
+    }
+
+    /// Embeds the sequences, the embeddings are in the same order as the sequences.
+    pub(crate) async fn run_batch(
+        &self,
+        kind: EmbeddingKind,
+        sequences: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<Vec<NormalizedEmbedding>, Error> {
         let prefix = match (kind, &self.prefix) {
             (EmbeddingKind::Query, Prefix { query, .. }) => query,
             (
@@ -295,23 +383,50 @@ impl Embedder {
                 },
             ) => content,
         };
-        let sequence = format!("{prefix}{sequence}");
+        let sequences = sequences
+            .into_iter()
+            .map(|sequence| format!("{prefix}{}", sequence.as_ref()))
+            .collect_vec();
+        if sequences.is_empty() {
+            return Ok(Vec::new());
+        }
 
         match &self.inner {
-            InnerEmbedder::Pipeline(embedder) => embedder
-                .run(sequence)
-                .map_err(InternalError::from_std)?
-                .normalize()
-                .map_err(InternalError::from_std),
+            InnerEmbedder::Pipeline(embedder) => sequences
+                .into_iter()
+                .map(|sequence| {
+                    embedder
+                        .run(sequence)
+                        .map_err(InternalError::from_std)
+                        .and_then(|embedding| {
+                            embedding.normalize().map_err(InternalError::from_std)
+                        })
+                        .map_err(Error::from)
+                })
+                .collect(),
             InnerEmbedder::Sagemaker {
                 client,
                 endpoint,
                 target_model,
+                max_batch_size,
                 ..
-            } => Self::run_sagemaker(client, endpoint, target_model.as_deref(), &sequence).await,
+            } => {
+                let mut embeddings = Vec::with_capacity(sequences.len());
+                for sequences in sequences.chunks(*max_batch_size) {
+                    embeddings.extend(
+                        Self::run_sagemaker(client, endpoint, target_model.as_deref(), sequences)
+                            .await?,
+                    );
+                }
+                Ok(embeddings)
+            }
             InnerEmbedder::OpenAi { client, url, .. } => {
-                Self::run_openai(client, url, &sequence).await
+                Self::run_openai(client, url, &sequences).await
             }
+            InnerEmbedder::Unavailable { model } => Err(EmbedderUnavailable {
+                model: model.clone(),
+            }
+            .into()),
         }
     }
 
@@ -319,10 +434,10 @@ impl Embedder {
         client: &aws_sdk_sagemakerruntime::Client,
         endpoint: &str,
         target_model: Option<&str>,
-        sequence: &str,
-    ) -> Result<NormalizedEmbedding, InternalError> {
+        sequences: &[String],
+    ) -> Result<Vec<NormalizedEmbedding>, Error> {
         let input = json!({
-            "inputs": [sequence],
+            "inputs": sequences,
         });
         let mut request = client
             .invoke_endpoint()
@@ -334,71 +449,120 @@ impl Embedder {
             request = request.target_model(target_model);
         };
 
-        let response = request.send().await.map_err(InternalError::from_std)?;
+        let response = request.send().await.map_err(|error| match &error {
+            SdkError::TimeoutError(_) | SdkError::DispatchFailure(_) => {
+                UpstreamUnavailable::new("sagemaker", error).into()
+            }
+            SdkError::ServiceError(service_error)
+                if matches!(
+                    service_error.err(),
+                    InvokeEndpointError::ServiceUnavailable(_)
+                        | InvokeEndpointError::ModelNotReadyException(_)
+                ) =>
+            {
+                UpstreamUnavailable::new("sagemaker", error).into()
+            }
+            _ => Error::from(InternalError::from_std(error)),
+        })?;
 
         let Some(body) = response.body() else {
-            return Err(InternalError::from_message(
-                "Received sagemaker response without body.",
-            ));
+            return Err(
+                InternalError::from_message("Received sagemaker response without body.").into(),
+            );
         };
 
-        let mut embeddings = serde_json::from_slice::<SagemakerResponse>(body.as_ref())
+        let embeddings = serde_json::from_slice::<SagemakerResponse>(body.as_ref())
             .map_err(InternalError::from_std)?
             .embeddings;
 
-        if embeddings.len() == 1 {
-            Ok(
-                embeddings.pop().unwrap(/* safe because we check that embeddings contains one item */),
-            )
+        if embeddings.len() == sequences.len() {
+            embeddings
+                .iter()
+                .map(|embedding| Self::normalize(embedding))
+                .collect()
         } else {
             Err(InternalError::from_message(format!(
-                "Unexpected sagemaker response. Expected 1 embedding, got {}",
-                embeddings.len()
-            )))
+                "Unexpected sagemaker response. Expected {} embeddings, got {}",
+                sequences.len(),
+                embeddings.len(),
+            ))
+            .into())
         }
     }
 
     async fn run_openai(
         client: &reqwest::Client,
         url: &Url,
-        sequence: &str,
-    ) -> Result<NormalizedEmbedding, InternalError> {
-        let input = json!({
-            "input": sequence,
-        });
+        sequences: &[String],
+    ) -> Result<Vec<NormalizedEmbedding>, Error> {
+        let input = if let [sequence] = sequences {
+            json!({
+                "input": sequence,
+            })
+        } else {
+            json!({
+                "input": sequences,
+            })
+        };
 
         let response: OpenAiResponse = client
             .post(url.clone())
             .json(&input)
             .send()
             .await
-            .map_err(InternalError::from_std)?
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|error| {
+                let is_transient = error.is_connect()
+                    || error.is_timeout()
+                    || matches!(
+                        error.status(),
+                        Some(status) if status.is_server_error()
+                            || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    );
+                if is_transient {
+                    UpstreamUnavailable::new("openai", error).into()
+                } else {
+                    Error::from(InternalError::from_std(error))
+                }
+            })?
             .json()
             .await
             .map_err(InternalError::from_std)?;
 
-        let embedding = response
+        if response.data.len() != sequences.len() {
+            return Err(InternalError::from_message("Invalid response format").into());
+        }
+
+        response
             .data
-            .into_iter()
-            .next()
-            .ok_or_else(|| InternalError::from_message("Invalid response format"))
-            .map(|data| data.embedding)?;
+            .iter()
+            .map(|data| Self::normalize(&data.embedding))
+            .collect()
+    }
 
-        embedding.normalize().map_err(InternalError::from_std)
+    fn normalize(embedding: &[f32]) -> Result<NormalizedEmbedding, Error> {
+        Embedding1::try_from_slice(embedding)
+            .and_then(Embedding1::normalize)
+            .map_err(|error| InternalError::from_std(error).into())
     }
 
-    pub(crate) fn embedding_size(&self) -> usize {
+    /// Gets the embedding size, which is unknown for unavailable models.
+    pub(crate) fn embedding_size(&self) -> Option<usize> {
         match &self.inner {
-            InnerEmbedder::Pipeline(embedder) => embedder.embedding_size(),
+            InnerEmbedder::Pipeline(embedder) => Some(embedder.embedding_size()),
             InnerEmbedder::Sagemaker { embedding_size, .. }
-            | InnerEmbedder::OpenAi { embedding_size, .. } => *embedding_size,
+            | InnerEmbedder::OpenAi { embedding_size, .. } => Some(*embedding_size),
+            InnerEmbedder::Unavailable { .. } => None,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use xayn_test_utils::asset::{ort, xaynia};
+    use xayn_test_utils::{
+        assert_approx_eq,
+        asset::{ort, xaynia},
+    };
 
     use super::*;
 
@@ -412,4 +576,34 @@ mod tests {
         let embedder = Embedder::load(&config).await.unwrap();
         embedder.run(EmbeddingKind::Query, "test").await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_embedder_batch() {
+        let config = Config::Pipeline(Pipeline {
+            directory: xaynia().unwrap().into(),
+            runtime: ort().unwrap().into(),
+            ..Pipeline::default()
+        });
+        let embedder = Embedder::load(&config).await.unwrap();
+
+        let sequences = ["this is one sentence", "this is another sentence"];
+        let embeddings = embedder
+            .run_batch(EmbeddingKind::Content, sequences)
+            .await
+            .unwrap();
+        assert_eq!(embeddings.len(), sequences.len());
+        for (sequence, embedding) in sequences.into_iter().zip(embeddings) {
+            let expected = embedder
+                .run(EmbeddingKind::Content, sequence)
+                .await
+                .unwrap();
+            assert_approx_eq!(f32, embedding, expected);
+        }
+
+        assert!(embedder
+            .run_batch(EmbeddingKind::Content, [""; 0])
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
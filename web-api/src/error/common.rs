@@ -16,13 +16,14 @@ use std::{
     borrow::Cow,
     fmt::{Debug, Display},
     ops::{Bound, RangeBounds},
+    time::Duration,
 };
 
 use actix_web::http::StatusCode;
 use derive_more::From;
 use displaydoc::Display;
 use serde::{Serialize, Serializer};
-use serde_json::Value;
+use serde_json::{json, Value};
 use thiserror::Error;
 use tracing::Level;
 use xayn_ai_bert::InvalidEmbedding;
@@ -186,6 +187,14 @@ pub(crate) enum InvalidDocumentProperties {
 
 impl_application_error!(InvalidDocumentProperties => BAD_REQUEST, INFO);
 
+/// The document properties violate the properties schema: {violations:?}
+#[derive(Debug, Error, Display, Serialize)]
+pub(crate) struct PropertiesSchemaViolation {
+    pub(crate) violations: Vec<String>,
+}
+
+impl_application_error!(PropertiesSchemaViolation => BAD_REQUEST, INFO);
+
 /// Malformed document tag: {0}
 #[derive(Debug, Error, Display, Serialize)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -255,6 +264,24 @@ pub(crate) struct InvalidDocumentCount {
 
 impl_application_error!(InvalidDocumentCount => BAD_REQUEST, INFO);
 
+/// Text too large. Got {size} bytes, expected at most {max}.
+#[derive(Debug, Error, Display, Serialize)]
+pub(crate) struct TextTooLarge {
+    pub(crate) size: usize,
+    pub(crate) max: usize,
+}
+
+impl_application_error!(TextTooLarge => PAYLOAD_TOO_LARGE, INFO);
+
+/// Request body too large. Got {size} bytes, expected at most {max}.
+#[derive(Debug, Error, Display, Serialize)]
+pub(crate) struct RequestBodyTooLarge {
+    pub(crate) size: usize,
+    pub(crate) max: usize,
+}
+
+impl_application_error!(RequestBodyTooLarge => PAYLOAD_TOO_LARGE, INFO);
+
 #[derive(Debug, Display, Error, Serialize)]
 pub(crate) enum ForbiddenDevOption {
     /// Dev options are not enabled for this tentant
@@ -311,6 +338,14 @@ pub(crate) struct FailedToSetSomeDocumentCandidates {
 
 impl_application_error!(FailedToSetSomeDocumentCandidates => BAD_REQUEST, INFO);
 
+/// Failed to update the embeddings of some documents.
+#[derive(Debug, Display, Error, Serialize)]
+pub(crate) struct FailedToReembedSomeDocuments {
+    pub(crate) documents: Vec<DocumentIdAsObject>,
+}
+
+impl_application_error!(FailedToReembedSomeDocuments => INTERNAL_SERVER_ERROR, ERROR);
+
 /// The history does not contains enough information.
 #[derive(Debug, Error, Display, Serialize)]
 pub(crate) struct HistoryTooSmall;
@@ -452,6 +487,78 @@ impl ApplicationError for PoolAcquisitionError {
     }
 }
 
+/// Upstream service {service} is temporarily unavailable: {message}
+#[derive(Debug, Display, Error)]
+pub(crate) struct UpstreamUnavailable {
+    service: &'static str,
+    message: String,
+}
+
+impl UpstreamUnavailable {
+    /// Delay after which clients are advised to retry the request.
+    const RETRY_AFTER: Duration = Duration::from_secs(5);
+
+    pub(crate) fn new(service: &'static str, error: impl Display) -> Self {
+        Self {
+            service,
+            message: error.to_string(),
+        }
+    }
+}
+
+impl ApplicationError for UpstreamUnavailable {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+
+    fn kind(&self) -> &str {
+        "UpstreamUnavailable"
+    }
+
+    fn level(&self) -> Level {
+        Level::WARN
+    }
+
+    fn encode_details(&self) -> Value {
+        json!({
+            "service": self.service,
+            "transient": true,
+        })
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        Some(Self::RETRY_AFTER)
+    }
+}
+
+/// The model {model} failed to load, the deployment runs in a degraded mode.
+#[derive(Debug, Error, Display, Serialize)]
+pub(crate) struct EmbedderUnavailable {
+    pub(crate) model: String,
+}
+
+impl_application_error!(EmbedderUnavailable => SERVICE_UNAVAILABLE, WARN);
+
+/// The ingestion of documents is paused for the tenant.
+#[derive(Debug, Error, Display, Serialize)]
+pub(crate) struct IngestionPaused;
+
+impl_application_error!(IngestionPaused => SERVICE_UNAVAILABLE, INFO);
+
+/// The ingestion of documents must be paused for the tenant during a re-embedding.
+#[derive(Debug, Error, Display, Serialize)]
+pub(crate) struct IngestionNotPaused;
+
+impl_application_error!(IngestionNotPaused => BAD_REQUEST, INFO);
+
+/// A re-embedding with the model {model} is already in progress for the tenant.
+#[derive(Debug, Error, Display, Serialize)]
+pub(crate) struct ReembeddingInProgress {
+    pub(crate) model: String,
+}
+
+impl_application_error!(ReembeddingInProgress => BAD_REQUEST, INFO);
+
 #[cfg(test)]
 mod tests {
     use super::*;
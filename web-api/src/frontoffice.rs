@@ -147,6 +147,32 @@ impl SemanticSearchConfig {
     }
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+#[cfg_attr(test, serde(deny_unknown_fields))]
+pub(crate) struct TextEmbeddingConfig {
+    /// Max number of bytes a text to embed can have.
+    pub(crate) max_text_size: usize,
+}
+
+impl Default for TextEmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            max_text_size: 2048,
+        }
+    }
+}
+
+impl TextEmbeddingConfig {
+    pub(crate) fn validate(&self) -> Result<(), SetupError> {
+        if self.max_text_size < 1 {
+            bail!("max_text_size needs to be at least 1");
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,4 +186,9 @@ mod tests {
     fn test_validate_default_semantic_search_config() {
         PersonalizationConfig::default().validate().unwrap();
     }
+
+    #[test]
+    fn test_validate_default_text_embedding_config() {
+        TextEmbeddingConfig::default().validate().unwrap();
+    }
 }
@@ -22,7 +22,7 @@ use xayn_web_api_shared::elastic::ScoreMap;
 
 use super::PersonalizationConfig;
 use crate::{
-    models::{DocumentTag, PersonalizedDocument, SnippetId},
+    models::{DocumentTag, PersonalizedDocument, SnippetId, BOOST_BOUNDS},
     rank_merge::{rrf, DEFAULT_RRF_K},
 };
 
@@ -74,6 +74,10 @@ fn rerank_by_tag_weight<'a>(
 /// The `score_weights` determine the ratios of the scores, it is ordered as
 /// `[interest_weight, tag_weight, elasticsearch_weight]`. The final score/ranking per document is
 /// calculated as the weighted sum of the scores.
+///
+/// The rank merged score of each document is then multiplied by its optional boost, which is
+/// clamped to the [`BOOST_BOUNDS`] and defaults to `1`. As the rank merged scores only depend on
+/// the ranks and not on the magnitude of the scores, a boost can move a document over many ranks.
 pub(crate) fn rerank(
     coi_system: &CoiSystem,
     documents: &mut [PersonalizedDocument],
@@ -99,7 +103,11 @@ pub(crate) fn rerank(
     .collect::<HashMap<SnippetId, _>>();
 
     for document in documents.iter_mut() {
-        document.score = *scores.get(&document.id).unwrap(/* rrf does create a score for each id*/);
+        let boost = document
+            .boost
+            .map_or(1., |boost| boost.clamp(BOOST_BOUNDS.0, BOOST_BOUNDS.1));
+        document.score =
+            *scores.get(&document.id).unwrap(/* rrf does create a score for each id*/) * boost;
     }
 
     documents.sort_unstable_by(|d1, d2| {
@@ -136,6 +144,7 @@ pub fn bench_rerank<S>(
                 .collect_vec()
                 .try_into()
                 .unwrap(),
+            boost: None,
             dev: None,
         })
         .collect_vec();
@@ -191,6 +200,7 @@ mod tests {
                     properties: None,
                     snippet: None,
                     tags,
+                    boost: None,
                     dev: None,
                 }
             })
@@ -301,4 +311,32 @@ mod tests {
             assert_approx_eq!(f32, reranked[&&one], reranked[&&id]);
         }
     }
+
+    #[test]
+    fn test_rerank_with_boost() {
+        let coi_system = CoiConfig::default().build();
+        let mut documents = mock_documents(5);
+        for (document, score) in documents.iter_mut().zip([1., 0.9, 0.8, 0.7, 0.6]) {
+            document.score = score;
+        }
+        documents[0].boost = Some(0.);
+        documents[3].boost = Some(100.);
+        documents[4].boost = Some(2.);
+
+        rerank(
+            &coi_system,
+            &mut documents,
+            &[],
+            &HashMap::new(),
+            [0., 0., 1.],
+            Utc::now(),
+        );
+        let ids = documents
+            .iter()
+            .map(|document| document.id.document_id().as_str())
+            .collect_vec();
+        assert_eq!(ids, ["3", "4", "1", "2", "0"]);
+        assert_approx_eq!(f32, documents[0].score, 10. / (DEFAULT_RRF_K + 4.));
+        assert_approx_eq!(f32, documents[4].score, 0.);
+    }
 }
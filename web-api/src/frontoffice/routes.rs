@@ -16,6 +16,7 @@ use actix_web::{
     web::{self, ServiceConfig},
     Responder,
 };
+use embedding::text_embedding;
 use interactions::interactions;
 use recommendations::{recommendations, user_recommendations};
 use semantic_search::semantic_search;
@@ -23,6 +24,7 @@ use semantic_search::semantic_search;
 use super::{PersonalizationConfig, SemanticSearchConfig};
 use crate::utils::deprecate;
 
+mod embedding;
 mod interactions;
 mod recommendations;
 mod semantic_search;
@@ -44,9 +46,11 @@ pub(crate) fn configure_service(config: &mut ServiceConfig) {
     let semantic_search = web::resource("/semantic_search").route(web::post().to(semantic_search));
     let recommendations_service =
         web::resource("/recommendations").route(web::post().to(recommendations));
+    let text_embedding = web::resource("/embedding").route(web::post().to(text_embedding));
 
     config
         .service(users)
         .service(semantic_search)
-        .service(recommendations_service);
+        .service(recommendations_service)
+        .service(text_embedding);
 }
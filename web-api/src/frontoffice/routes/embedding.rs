@@ -0,0 +1,71 @@
+// Copyright 2023 Xayn AG
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use actix_web::{
+    web::{Data, Json},
+    Responder,
+};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use xayn_ai_bert::NormalizedEmbedding;
+
+use super::super::TextEmbeddingConfig;
+use crate::{
+    app::{AppState, TenantState},
+    embedding::EmbeddingKind,
+    error::common::{BadRequest, TextTooLarge},
+    Error,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct UnvalidatedTextEmbeddingRequest {
+    text: String,
+}
+
+impl UnvalidatedTextEmbeddingRequest {
+    fn validate(self, config: &TextEmbeddingConfig) -> Result<String, Error> {
+        let size = self.text.len();
+        if size == 0 {
+            return Err(BadRequest::from("text must not be empty").into());
+        }
+        if size > config.max_text_size {
+            return Err(TextTooLarge {
+                size,
+                max: config.max_text_size,
+            }
+            .into());
+        }
+
+        Ok(self.text)
+    }
+}
+
+#[derive(Serialize)]
+struct TextEmbeddingResponse {
+    embedding: NormalizedEmbedding,
+}
+
+/// Embeds the text with the tenants model without touching any of the tenants data.
+#[instrument(skip_all)]
+pub(super) async fn text_embedding(
+    state: Data<AppState>,
+    Json(body): Json<UnvalidatedTextEmbeddingRequest>,
+    TenantState(_, embedder): TenantState,
+) -> Result<impl Responder, Error> {
+    let text = body.validate(state.config.as_ref())?;
+    let embedding = embedder.run(EmbeddingKind::Content, &text).await?;
+
+    Ok(Json(TextEmbeddingResponse { embedding }))
+}
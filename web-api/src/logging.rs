@@ -20,6 +20,7 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, Dispatch, Level};
 use tracing_subscriber::{
     filter::{LevelFilter, Targets},
+    fmt::format::FmtSpan,
     layer::SubscriberExt,
     util::{SubscriberInitExt, TryInitError},
 };
@@ -61,6 +62,38 @@ pub struct Config {
     #[serde(with = "serde_level_filter")]
     pub level: LevelFilter,
     pub install_panic_hook: bool,
+    /// The span events which are logged in addition to the events.
+    pub span_events: Vec<SpanEvent>,
+}
+
+/// A span lifecycle event, see [`FmtSpan`].
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanEvent {
+    /// A span was created.
+    New,
+    /// A span was entered.
+    Enter,
+    /// A span was exited.
+    Exit,
+    /// A span was closed, includes the busy and idle time of the span.
+    Close,
+}
+
+impl SpanEvent {
+    fn fmt_span(span_events: &[Self]) -> FmtSpan {
+        span_events
+            .iter()
+            .fold(FmtSpan::NONE, |fmt_span, span_event| {
+                fmt_span
+                    | match span_event {
+                        Self::New => FmtSpan::NEW,
+                        Self::Enter => FmtSpan::ENTER,
+                        Self::Exit => FmtSpan::EXIT,
+                        Self::Close => FmtSpan::CLOSE,
+                    }
+            })
+    }
 }
 
 impl Default for Config {
@@ -69,6 +102,7 @@ impl Default for Config {
             file: None,
             level: LevelFilter::INFO,
             install_panic_hook: true,
+            span_events: Vec::new(),
         }
     }
 }
@@ -83,6 +117,7 @@ pub fn initialize_global(config: &Config) -> Result<(), TryInitError> {
     let dispatch = create_trace_dispatch(
         config.level,
         config.file.as_ref().map(|f| f.relative()).as_deref(),
+        &SpanEvent::fmt_span(&config.span_events),
     );
     dispatch.try_init()?;
     if config.install_panic_hook {
@@ -91,10 +126,15 @@ pub fn initialize_global(config: &Config) -> Result<(), TryInitError> {
     Ok(())
 }
 
-fn create_trace_dispatch(level: LevelFilter, file: Option<&Path>) -> Dispatch {
+fn create_trace_dispatch(
+    level: LevelFilter,
+    file: Option<&Path>,
+    span_events: &FmtSpan,
+) -> Dispatch {
     let subscriber = tracing_subscriber::registry();
 
     let stdout_log = tracing_subscriber::fmt::layer()
+        .with_span_events(span_events.clone())
         .json()
         .flatten_event(true)
         .with_current_span(false);
@@ -115,6 +155,7 @@ fn create_trace_dispatch(level: LevelFilter, file: Option<&Path>) -> Dispatch {
                     tracing_subscriber::fmt::layer()
                         .with_writer(writer)
                         .with_ansi(false)
+                        .with_span_events(span_events.clone())
                         .json()
                 })
         })
@@ -146,3 +187,35 @@ fn init_panic_logging() {
         }
     }));
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use tracing::{dispatcher, info, info_span};
+
+    use super::*;
+
+    #[test]
+    fn test_span_events_are_logged() {
+        let file = env::temp_dir().join(format!("web_api_span_events_{}.log", process::id()));
+        let dispatch = create_trace_dispatch(
+            LevelFilter::INFO,
+            Some(&file),
+            &SpanEvent::fmt_span(&[SpanEvent::New, SpanEvent::Close]),
+        );
+        dispatcher::with_default(&dispatch, || {
+            info_span!("test_span").in_scope(|| info!("test_event"));
+        });
+        drop(dispatch);
+        let log = fs::read_to_string(&file).unwrap();
+        fs::remove_file(file).unwrap();
+
+        let messages = log
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .map(|line| line["fields"]["message"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["new", "test_event", "close"]);
+    }
+}
@@ -11,6 +11,7 @@
 //
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
+pub(crate) mod body_limit;
 pub(crate) mod json_error;
 pub(crate) mod request_context;
 pub(crate) mod tracing;
@@ -0,0 +1,49 @@
+// Copyright 2023 Xayn AG
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::future::Future;
+
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse},
+    http::header::CONTENT_LENGTH,
+};
+use futures_util::future::{self, Either};
+
+use crate::{error::common::RequestBodyTooLarge, Error};
+
+/// Rejects requests which announce a body larger than `max` bytes before any of it is read.
+///
+/// Bodies without a `Content-Length` are limited by the payload extractors while streaming.
+pub(crate) fn reject_oversized_bodies<S, B>(
+    max: usize,
+    request: ServiceRequest,
+    service: &S,
+) -> impl Future<Output = Result<ServiceResponse<B>, actix_web::Error>> + 'static
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+{
+    let size = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|size| size.to_str().ok()?.parse::<usize>().ok());
+
+    match size {
+        Some(size) if size > max => {
+            let error = Error::from(RequestBodyTooLarge { size, max });
+            Either::Left(future::err(error.into()))
+        }
+        _ => Either::Right(service.call(request)),
+    }
+}
@@ -41,7 +41,7 @@ where
     B: MessageBody + Debug + 'static,
 {
     let request_id = match RequestContext::try_extract_from_request(request.request(), |context| {
-        context.request_id
+        context.request_id.clone()
     }) {
         Ok(id) => id,
         Err(error) => {
@@ -57,10 +57,11 @@ where
         }
     };
 
+    let response_request_id = request_id.clone();
     Either::Right(
         service
             .call(request)
-            .map_ok(move |resp| wrap_service_response(resp, request_id))
+            .map_ok(move |resp| wrap_service_response(resp, response_request_id))
             // note that endpoints _directly_ turn any `Err(..)` into an `Ok(err_resp)`,
             // so we will only see middleware errors here, never endpoint errors
             .map_err(move |resp| WrappedMiddlewareError::wrap(resp, request_id)),
@@ -142,7 +143,7 @@ impl ResponseError for WrappedMiddlewareError {
 
             JsonErrorResponseBuilder::render(
                 self.status_code().as_str(),
-                self.request_id,
+                self.request_id.clone(),
                 &msg.map_or(Value::Null, |msg| json!({ "message": msg })),
             )
             .apply_to(response)
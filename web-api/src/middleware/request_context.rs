@@ -17,6 +17,7 @@ use std::{future::Future, str, sync::Arc};
 use actix_web::{
     body::BoxBody,
     dev::{Service, ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue},
     HttpMessage,
     HttpRequest,
 };
@@ -64,21 +65,60 @@ pub(crate) struct AccessError {
     method: &'static str,
 }
 
-#[derive(Clone, Copy, Debug, derive_more::Display, Serialize)]
+/// The id of a request.
+///
+/// Either taken from the `X-Request-Id` header of the request or generated.
+#[derive(Clone, Debug, derive_more::Display, Serialize)]
 #[serde(transparent)]
-pub(crate) struct RequestId(Uuid);
+pub(crate) struct RequestId(Arc<str>);
 
 task_local! {
     static CURRENT_REQUEST_ID: RequestId;
 }
 
 impl RequestId {
+    /// The maximum length of a request id supplied by the client.
+    const MAX_LEN: usize = 128;
+
     pub(crate) fn generate() -> Self {
-        Self(Uuid::new_v4())
+        Self(Uuid::new_v4().to_string().into())
+    }
+
+    pub(crate) fn missing() -> Self {
+        Self(Uuid::nil().to_string().into())
+    }
+
+    /// Parses a client supplied request id.
+    ///
+    /// The id must be non-empty, at most 128 bytes long and consist of visible ascii characters.
+    fn try_parse_ascii(ascii: &[u8]) -> Option<Self> {
+        if ascii.is_empty()
+            || ascii.len() > Self::MAX_LEN
+            || !ascii.iter().all(u8::is_ascii_graphic)
+        {
+            return None;
+        }
+        str::from_utf8(ascii).ok().map(|id| Self(id.into()))
     }
 
-    pub(crate) const fn missing() -> Self {
-        Self(Uuid::nil())
+    /// Takes the request id from the `X-Request-Id` header if it is valid, generates one otherwise.
+    fn extract_or_generate(request: &ServiceRequest) -> Self {
+        request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| Self::try_parse_ascii(value.as_bytes()))
+            .unwrap_or_else(Self::generate)
+    }
+
+    fn to_header_value(&self) -> HeaderValue {
+        HeaderValue::from_str(&self.0).unwrap_or_else(|_| unreachable!(/* id is visible ascii */))
+    }
+
+    fn insert_into_response(&self, response: &mut ServiceResponse<BoxBody>) {
+        response.headers_mut().insert(
+            HeaderName::from_static(REQUEST_ID_HEADER),
+            self.to_header_value(),
+        );
     }
 
     pub(crate) fn wrap_future<F>(self, future: F) -> TaskLocalFuture<RequestId, F>
@@ -90,7 +130,7 @@ impl RequestId {
 
     pub(crate) fn extract_from_task_local_storage() -> Result<RequestId, AccessError> {
         CURRENT_REQUEST_ID
-            .try_with(|id| *id)
+            .try_with(RequestId::clone)
             .map_err(|_| AccessError {
                 method: "extract_from_task_local_storage",
             })
@@ -101,7 +141,8 @@ impl RequestId {
 ///
 /// This makes the `RequestId` and `TenantId` available as extensions and sets up tracing for all calls.
 ///
-/// The `TenantId` is required.
+/// The `TenantId` is required. The `RequestId` is taken from the `X-Request-Id` header if present
+/// and valid, otherwise a new one is generated. Either way it is echoed back in the response.
 pub(crate) fn setup_request_context<S>(
     legacy_tenant: Option<&TenantId>,
     request: ServiceRequest,
@@ -111,19 +152,20 @@ where
     S: Service<ServiceRequest, Response = ServiceResponse<BoxBody>, Error = actix_web::Error>,
     S::Future: 'static,
 {
-    let request_id = RequestId::generate();
+    let request_id = RequestId::extract_or_generate(&request);
 
     let tenant_id = match extract_tenant_id(legacy_tenant, &request) {
         Ok(id) => id,
         Err(error) => {
-            let response = middleware_failure(
+            let mut response = middleware_failure(
                 "setup_request_context",
                 request,
-                Some(request_id),
+                Some(request_id.clone()),
                 None,
                 error,
                 Level::ERROR,
             );
+            request_id.insert_into_response(&mut response);
             return Either::Left(future::ok(response));
         }
     };
@@ -142,23 +184,35 @@ where
 
     let context = Arc::new(RequestContext {
         tenant_id,
-        request_id,
+        request_id: request_id.clone(),
     });
 
     request.extensions_mut().insert(context);
+    // middleware errors are turned into responses after this middleware, hence we need to do it
+    // here already to be able to add the header
+    let http_request = request.request().clone();
 
     Either::Right(
-        request_id.wrap_future(
+        request_id.clone().wrap_future(
             service
                 .call(request)
                 .instrument(span.clone())
-                .inspect(|_| trace!(parent: span, "request processed")),
+                .inspect(|_| trace!(parent: span, "request processed"))
+                .map(move |result| {
+                    let mut response = result
+                        .unwrap_or_else(|error| ServiceResponse::from_err(error, http_request));
+                    request_id.insert_into_response(&mut response);
+                    Ok(response)
+                }),
         ),
     )
 }
 
 const TENANT_ID_HEADER: &str = "X-Xayn-Tenant-Id";
 
+// must be lowercase to be usable with `HeaderName::from_static()`
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
 fn extract_tenant_id(
     legacy_tenant: Option<&TenantId>,
     request: &ServiceRequest,
@@ -231,6 +285,22 @@ mod tests {
         assert_eq!(trim_ascii_end(b" \n ab\t cd  \t"), b" \n ab\t cd");
     }
 
+    #[test]
+    fn test_parsing_request_id_from_ascii() {
+        assert!(RequestId::try_parse_ascii(b"").is_none());
+        assert!(RequestId::try_parse_ascii(b"a b").is_none());
+        assert!(RequestId::try_parse_ascii("ä".as_bytes()).is_none());
+        assert!(RequestId::try_parse_ascii(&[65u8; 128]).is_some());
+        assert!(RequestId::try_parse_ascii(&[65u8; 129]).is_none());
+
+        assert_eq!(
+            RequestId::try_parse_ascii(b"req-1:Ab/9")
+                .unwrap()
+                .to_string(),
+            "req-1:Ab/9",
+        );
+    }
+
     #[test]
     fn test_parsing_tenant_id_from_ascii() {
         assert!(TenantId::try_parse_ascii(b"").is_err());
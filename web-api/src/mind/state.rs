@@ -90,6 +90,7 @@ impl State {
                 Ok::<_, Panic>(DocumentForIngestion {
                     id: document.id,
                     original_sha256: Sha256Hash::calculate(document.snippet.as_bytes()),
+                    content_sha256: Sha256Hash::calculate_normalized(&document.snippet),
                     snippets: vec![DocumentContent {
                         snippet: document.snippet,
                         embedding,
@@ -14,12 +14,13 @@
 
 use std::{
     borrow::{Borrow, Cow},
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::Debug,
     ops::{RangeBounds, RangeInclusive},
     str::FromStr,
 };
 
+use base64::{engine::general_purpose, Engine as _};
 use chrono::DateTime;
 use derive_more::{Deref, DerefMut, Display, Into};
 use once_cell::sync::Lazy;
@@ -381,8 +382,30 @@ impl DocumentProperties {
 
         Ok(Self(properties))
     }
+
+    /// Removes the property, if it exists.
+    pub(crate) fn remove(&mut self, property_id: &DocumentPropertyId) -> Option<DocumentProperty> {
+        self.0.remove(property_id)
+    }
+
+    /// Gets the ranking boost of the document from the [`BOOST_PROPERTY`], if it's a number.
+    ///
+    /// Other values of the property are ignored and the boost is clamped to the [`BOOST_BOUNDS`].
+    pub(crate) fn boost(&self) -> Option<f32> {
+        #[allow(clippy::cast_possible_truncation)]
+        self.0
+            .get(BOOST_PROPERTY)
+            .and_then(|boost| boost.as_f64())
+            .map(|boost| (boost as f32).clamp(BOOST_BOUNDS.0, BOOST_BOUNDS.1))
+    }
 }
 
+/// The property which can be used to boost the personalized ranking of a document.
+pub(crate) const BOOST_PROPERTY: &str = "boost";
+
+/// The bounds of the ranking boost of a document.
+pub(crate) const BOOST_BOUNDS: (f32, f32) = (0., 10.);
+
 impl IntoIterator for DocumentProperties {
     type Item = <HashMap<DocumentPropertyId, DocumentProperty> as IntoIterator>::Item;
     type IntoIter = <HashMap<DocumentPropertyId, DocumentProperty> as IntoIterator>::IntoIter;
@@ -453,6 +476,11 @@ pub(crate) struct PersonalizedDocument {
     /// The tags associated to the document.
     pub(crate) tags: DocumentTags,
 
+    /// The ranking boost of the document, see [`DocumentProperties::boost()`].
+    ///
+    /// In difference to the properties the boost is always loaded from the db.
+    pub(crate) boost: Option<f32>,
+
     /// Additional data about the document that can be helpful while tuning or debugging the system.
     pub(crate) dev: Option<DocumentDevData>,
 }
@@ -492,6 +520,9 @@ pub(crate) struct DocumentForIngestion {
     /// The sha256 hash of the original document provided by the client.
     pub(crate) original_sha256: Sha256Hash,
 
+    /// The sha256 hash of the normalized content used for deduplication.
+    pub(crate) content_sha256: Sha256Hash,
+
     /// Snippet used to calculate embeddings for a document.
     pub(crate) snippets: Vec<DocumentContent>,
 
@@ -508,7 +539,29 @@ pub(crate) struct DocumentForIngestion {
     pub(crate) is_candidate: bool,
 }
 
-#[derive(Clone, Debug, PartialEq, Type)]
+/// Represents an ingested document whose snippets are embedded again.
+#[derive(Clone, Debug)]
+pub(crate) struct DocumentForReembedding {
+    /// Unique identifier of the document.
+    pub(crate) id: DocumentId,
+
+    /// The snippets of the document ordered by their sub ids.
+    pub(crate) snippets: Vec<DocumentContent>,
+
+    /// Method used to preprocess the document during the ingestion.
+    pub(crate) preprocessing_step: PreprocessingStep,
+
+    /// Contents of the document properties.
+    pub(crate) properties: DocumentProperties,
+
+    /// The tags associated to the document.
+    pub(crate) tags: DocumentTags,
+
+    /// Indicates if the document is considered for recommendations.
+    pub(crate) is_candidate: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Type)]
 #[sqlx(transparent)]
 pub(crate) struct Sha256Hash([u8; 32]);
 
@@ -522,6 +575,18 @@ impl Sha256Hash {
         hasher.update(document);
         Self(hasher.finalize().into())
     }
+
+    /// Calculates the hash of the text with normalized case and whitespace.
+    pub(crate) fn calculate_normalized(text: &str) -> Self {
+        let mut hasher = Sha256::new();
+        for (i, word) in text.split_whitespace().enumerate() {
+            if i > 0 {
+                hasher.update(b" ");
+            }
+            hasher.update(word.to_lowercase().as_bytes());
+        }
+        Self(hasher.finalize().into())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -540,6 +605,20 @@ pub(crate) struct ExcerptedDocument {
     pub(crate) is_candidate: bool,
 }
 
+impl ExcerptedDocument {
+    /// Calculates an entity tag which is stable for identical documents.
+    pub(crate) fn entity_tag(&self) -> String {
+        let properties = self.properties.iter().collect::<BTreeMap<_, _>>();
+        let data = serde_json::to_vec(&(properties, &self.tags, self.is_candidate))
+            .unwrap(/* properties and tags are always serializable */);
+
+        let mut hasher = Sha256::new();
+        hasher.update(self.original_sha256.0);
+        hasher.update(data);
+        general_purpose::URL_SAFE_NO_PAD.encode(hasher.finalize())
+    }
+}
+
 /// The preprocessing step used on the raw document.
 // Note: The same input parameter (e.g. split) can over time
 //       map to different variants, e.g. now it maps to `CuttersSplit`
@@ -572,6 +651,8 @@ impl PreprocessingStep {
 
 #[cfg(test)]
 mod tests {
+    use serde_json::json;
+
     use super::*;
     use crate::frontoffice::SemanticSearchConfig;
 
@@ -731,4 +812,33 @@ mod tests {
             }))
         );
     }
+
+    #[test]
+    fn test_boost_property() {
+        let properties = |boost: Value| {
+            let properties = [(BOOST_PROPERTY.try_into().unwrap(), DocumentProperty(boost))];
+            DocumentProperties::new(properties.into(), 0, 0).unwrap()
+        };
+        assert_eq!(properties(json!(2.5)).boost(), Some(2.5));
+        assert_eq!(properties(json!(-1)).boost(), Some(BOOST_BOUNDS.0));
+        assert_eq!(properties(json!(1e300)).boost(), Some(BOOST_BOUNDS.1));
+        assert_eq!(properties(Value::Null).boost(), None);
+        assert_eq!(properties(json!("2.5")).boost(), None);
+    }
+
+    #[test]
+    fn test_sha256_normalized() {
+        assert_eq!(
+            Sha256Hash::calculate_normalized("Hello  World\n"),
+            Sha256Hash::calculate_normalized(" hello world"),
+        );
+        assert_eq!(
+            Sha256Hash::calculate_normalized("hello world"),
+            Sha256Hash::calculate(b"hello world"),
+        );
+        assert_ne!(
+            Sha256Hash::calculate_normalized("hello world"),
+            Sha256Hash::calculate_normalized("helloworld"),
+        );
+    }
 }
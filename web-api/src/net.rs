@@ -22,7 +22,7 @@ use actix_cors::Cors;
 use actix_web::{
     dev::ServerHandle,
     middleware,
-    web::{self, JsonConfig, ServiceConfig},
+    web::{self, JsonConfig, PayloadConfig, ServiceConfig},
     App,
     HttpResponse,
     HttpServer,
@@ -42,10 +42,14 @@ use tracing::{
 };
 use xayn_web_api_shared::{request::TenantId, serde::serde_duration_as_seconds};
 
-use crate::middleware::{
-    json_error::wrap_non_json_errors,
-    request_context::setup_request_context,
-    tracing::new_http_server_with_subscriber,
+use crate::{
+    app::ready,
+    middleware::{
+        body_limit::reject_oversized_bodies,
+        json_error::wrap_non_json_errors,
+        request_context::setup_request_context,
+        tracing::new_http_server_with_subscriber,
+    },
 };
 
 /// Configuration for roughly network/connection layer specific configurations.
@@ -64,6 +68,9 @@ pub struct Config {
     /// Client request timeout in seconds
     #[serde(with = "serde_duration_as_seconds")]
     pub(crate) client_request_timeout: Duration,
+
+    /// Maximum size of a request body in bytes
+    pub(crate) max_request_body_bytes: usize,
 }
 
 impl Default for Config {
@@ -72,6 +79,7 @@ impl Default for Config {
             bind_to: SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 1), 4252).into(),
             keep_alive: Duration::from_secs(61),
             client_request_timeout: Duration::from_secs(0),
+            max_request_body_bytes: 100 * 1024 * 1024,
         }
     }
 }
@@ -85,8 +93,9 @@ pub(crate) fn start_actix_server(
     attach_ops: impl Fn(&mut ServiceConfig) + Send + Clone + 'static,
     on_shutdown: Box<dyn FnOnce() -> BoxFuture<'static, ()>>,
 ) -> Result<AppHandle, anyhow::Error> {
-    // limits are handled by the infrastructure
-    let json_config = JsonConfig::default().limit(u32::MAX as usize);
+    let max_body_bytes = net_config.max_request_body_bytes;
+    let json_config = JsonConfig::default().limit(max_body_bytes);
+    let payload_config = PayloadConfig::default().limit(max_body_bytes);
     let subscriber = dispatcher::get_default(Dispatch::clone);
     let server = new_http_server_with_subscriber!(subscriber, move || {
         let legacy_tenant = legacy_tenant.clone();
@@ -96,6 +105,12 @@ pub(crate) fn start_actix_server(
                     .route(web::get().to(HttpResponse::Ok))
                     .wrap(Cors::default()),
             )
+            .service(
+                web::scope("/ready")
+                    .configure(&attach_state)
+                    .route("", web::get().to(ready))
+                    .wrap(Cors::default()),
+            )
             .service(
                 web::scope("/_ops")
                     .configure(&attach_state)
@@ -105,8 +120,10 @@ pub(crate) fn start_actix_server(
             .service({
                 web::scope("")
                     .app_data(json_config.clone())
+                    .app_data(payload_config.clone())
                     .configure(&attach_state)
                     .configure(&attach_app)
+                    .wrap_fn(move |r, s| reject_oversized_bodies(max_body_bytes, r, s))
                     .wrap_fn(wrap_non_json_errors)
                     .wrap_fn(move |r, s| setup_request_context(legacy_tenant.as_ref(), r, s))
                     .wrap(middleware::Compress::default())
@@ -16,6 +16,7 @@ pub(crate) mod elastic;
 #[cfg(test)]
 pub(crate) mod memory;
 pub(crate) mod postgres;
+pub(crate) mod properties_schema;
 pub(crate) mod property_filter;
 mod utils;
 
@@ -31,7 +32,10 @@ use xayn_ai_coi::Coi;
 use xayn_web_api_db_ctrl::{tenant::Tenant, LegacyTenantInfo, Silo};
 use xayn_web_api_shared::{postgres as postgres_shared, request::TenantId};
 
-use self::property_filter::{IndexedPropertiesSchema, IndexedPropertiesSchemaUpdate};
+use self::{
+    properties_schema::PropertiesJsonSchema,
+    property_filter::{IndexedPropertiesSchema, IndexedPropertiesSchemaUpdate},
+};
 use crate::{
     app::SetupError,
     backoffice::IngestionConfig,
@@ -39,6 +43,7 @@ use crate::{
     models::{
         self,
         DocumentForIngestion,
+        DocumentForReembedding,
         DocumentId,
         DocumentPropertyId,
         DocumentQuery,
@@ -46,6 +51,7 @@ use crate::{
         DocumentTags,
         ExcerptedDocument,
         PersonalizedDocument,
+        Sha256Hash,
         SnippetForInteraction,
         SnippetId,
         SnippetOrDocumentId,
@@ -298,6 +304,64 @@ pub(crate) trait IndexedProperties {
     ) -> Result<IndexedPropertiesSchema, Error>;
 }
 
+#[async_trait(?Send)]
+pub(crate) trait PropertiesSchema {
+    async fn load(&self) -> Result<Option<PropertiesJsonSchema>, Error>;
+
+    async fn store(&self, schema: Option<&PropertiesJsonSchema>) -> Result<(), Error>;
+}
+
+/// The state of a re-embedding of the documents of a tenant into a new index.
+#[derive(Clone, Debug)]
+pub(crate) struct ReembeddingState {
+    pub(crate) model: String,
+    pub(crate) es_index_name: String,
+    /// The last document which has been re-embedded, if any.
+    pub(crate) last_document_id: Option<DocumentId>,
+}
+
+#[async_trait(?Send)]
+pub(crate) trait Reembedding {
+    /// Starts a re-embedding with the model into the index.
+    ///
+    /// Returns the state of the re-embedding which is already in progress instead, if any.
+    async fn start(&self, model: &str, es_index_name: &str) -> Result<ReembeddingState, Error>;
+
+    /// Gets the next documents ordered by their ids, which come after the id if one is given.
+    async fn get(
+        &self,
+        after: Option<&DocumentId>,
+        count: usize,
+    ) -> Result<Vec<DocumentForReembedding>, Error>;
+
+    /// Stores the new embeddings of the documents and inserts the candidates into the new index.
+    ///
+    /// The last document is stored as the progress of the re-embedding. If some documents fail to
+    /// be inserted, nothing is stored and the failed ones are returned.
+    async fn update(
+        &self,
+        state: &ReembeddingState,
+        documents: &[DocumentForReembedding],
+    ) -> Result<Warning<DocumentId>, Error>;
+
+    /// Replaces the embeddings of all documents with the new ones and ends the re-embedding.
+    ///
+    /// The centers of interest are deleted, because they were computed from the old embeddings.
+    async fn finish(&self) -> Result<(), Error>;
+
+    /// Counts the documents up to and including the id and all documents.
+    async fn progress(&self, until: Option<&DocumentId>) -> Result<(usize, usize), Error>;
+}
+
+#[async_trait(?Send)]
+pub(crate) trait ContentHash {
+    /// Gets the ids of the documents with any of the normalized content hashes.
+    async fn get_ids(
+        &self,
+        hashes: &[Sha256Hash],
+    ) -> Result<HashMap<Sha256Hash, DocumentId>, Error>;
+}
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(default)]
 #[cfg_attr(test, serde(deny_unknown_fields))]
@@ -335,6 +399,7 @@ pub(crate) async fn initialize_silo(
     config: &Config,
     tenant_config: &tenants::Config,
     embedding_sizes: HashMap<String, usize>,
+    unavailable_models: Vec<String>,
 ) -> Result<(Silo, Option<TenantId>), SetupError> {
     let silo = Silo::new(
         config.postgres.clone(),
@@ -346,7 +411,8 @@ pub(crate) async fn initialize_silo(
             }),
         embedding_sizes,
     )
-    .await?;
+    .await?
+    .with_unavailable_models(unavailable_models);
 
     // FIXME: remove this once we have a proper separation between
     //        a admin pg user owning the db structure and a web-api-mt
@@ -192,21 +192,58 @@ impl Client {
     pub(super) async fn freshly_insert_documents(
         &self,
         documents: impl IntoIterator<Item = &models::DocumentForIngestion>,
+    ) -> Result<Warning<DocumentId>, Error> {
+        self.insert_snippets(documents.into_iter().map(|document| {
+            (
+                &document.id,
+                document.snippets.as_slice(),
+                &document.properties,
+                &document.tags,
+            )
+        }))
+        .await
+    }
+
+    pub(super) async fn insert_reembedded_documents(
+        &self,
+        documents: impl IntoIterator<Item = &models::DocumentForReembedding>,
+    ) -> Result<Warning<DocumentId>, Error> {
+        self.insert_snippets(documents.into_iter().map(|document| {
+            (
+                &document.id,
+                document.snippets.as_slice(),
+                &document.properties,
+                &document.tags,
+            )
+        }))
+        .await
+    }
+
+    async fn insert_snippets<'a>(
+        &self,
+        documents: impl IntoIterator<
+            Item = (
+                &'a DocumentId,
+                &'a [DocumentContent],
+                &'a DocumentProperties,
+                &'a DocumentTags,
+            ),
+        >,
     ) -> Result<Warning<DocumentId>, Error> {
         let mut snippets = documents
             .into_iter()
-            .flat_map(|document| {
-                document.snippets.iter().enumerate().flat_map(
-                    |(idx, DocumentContent { snippet, embedding })| {
+            .flat_map(|(document_id, snippets, properties, tags)| {
+                snippets.iter().enumerate().flat_map(
+                    move |(idx, DocumentContent { snippet, embedding })| {
                         #[allow(clippy::cast_possible_truncation)]
-                        let id = SnippetId::new(document.id.clone(), idx as _);
+                        let id = SnippetId::new(document_id.clone(), idx as _);
                         let header =
                             serde_json::to_value(BulkInstruction::Index { id: id.to_es_id() });
                         let data = serde_json::to_value(Document {
                             snippet,
-                            properties: &document.properties,
+                            properties,
                             embedding,
-                            tags: &document.tags,
+                            tags,
                             parent: id.document_id(),
                         });
 
@@ -25,6 +25,11 @@ impl Client {
     pub(crate) fn builder(config: elastic::Config) -> Result<ClientBuilder, SetupError> {
         elastic::Client::new(config).map(ClientBuilder)
     }
+
+    /// Creates a client which accesses the index instead of the alias of the tenant.
+    pub(crate) fn with_index(&self, index: &str) -> Self {
+        Client(self.0.with_index(index))
+    }
 }
 
 #[derive(Clone)]
@@ -32,6 +37,6 @@ pub(crate) struct ClientBuilder(elastic::Client);
 
 impl ClientBuilder {
     pub(crate) fn build_for(&self, tenant: &Tenant) -> Client {
-        Client(self.0.with_index(&tenant.es_index_name))
+        Client(self.0.with_index(&tenant.es_alias_name))
     }
 }
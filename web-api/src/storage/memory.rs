@@ -286,6 +286,7 @@ impl storage::Document for Storage {
                             properties: include_properties.then(|| document.properties.clone()),
                             snippet: include_snippet.then(|| document.snippet.clone()),
                             tags: document.tags.clone(),
+                            boost: document.properties.boost(),
                             dev: None,
                         })
                 })
@@ -359,6 +360,7 @@ impl storage::Document for Storage {
                             .then(|| document.properties.clone()),
                         snippet: params.include_snippet.then(|| document.snippet.clone()),
                         tags: document.tags.clone(),
+                        boost: document.properties.boost(),
                         dev: None,
                     })
                 }
@@ -758,6 +760,7 @@ mod tests {
             .map(|(id, embedding)| DocumentForIngestion {
                 id: id.document_id().clone(),
                 original_sha256: Sha256Hash::calculate(b"snippet"),
+                content_sha256: Sha256Hash::calculate_normalized("snippet"),
                 snippets: vec![DocumentContent {
                     snippet: DocumentSnippet::new_with_length_constraint("snippet", 1..=100)
                         .unwrap(),
@@ -833,6 +836,7 @@ mod tests {
             vec![DocumentForIngestion {
                 id: doc_id.document_id().clone(),
                 original_sha256: Sha256Hash::calculate(snippet.as_bytes()),
+                content_sha256: Sha256Hash::calculate_normalized(&snippet),
                 snippets: vec![DocumentContent {
                     snippet: snippet.clone(),
                     embedding: embedding.clone(),
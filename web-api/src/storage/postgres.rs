@@ -45,6 +45,7 @@ use xayn_ai_coi::{Coi, CoiId, CoiStats};
 use xayn_web_api_shared::elastic::ScoreMap;
 
 use super::{
+    properties_schema::PropertiesJsonSchema,
     property_filter::{
         IndexedPropertiesSchema,
         IndexedPropertiesSchemaUpdate,
@@ -61,6 +62,7 @@ use crate::{
         DocumentContent,
         DocumentDevData,
         DocumentForIngestion,
+        DocumentForReembedding,
         DocumentId,
         DocumentProperties,
         DocumentProperty,
@@ -70,14 +72,17 @@ use crate::{
         DocumentTags,
         ExcerptedDocument,
         PersonalizedDocument,
+        PreprocessingStep,
         RawScores,
         Sha256Hash,
         SnippetForInteraction,
         SnippetId,
         SnippetOrDocumentId,
         UserId,
+        BOOST_BOUNDS,
+        BOOST_PROPERTY,
     },
-    storage::{self, utils::SqlxPushTupleExt, KnnSearchParams, Storage, Warning},
+    storage::{self, utils::SqlxPushTupleExt, KnnSearchParams, ReembeddingState, Storage, Warning},
     Error,
 };
 
@@ -112,19 +117,21 @@ impl Database {
             "INSERT INTO document (
                 document_id,
                 original_sha256,
+                content_sha256,
                 preprocessing_step,
                 properties,
                 tags,
                 is_candidate
             ) ",
         );
-        for chunk in documents.chunks(Self::BIND_LIMIT / 6) {
+        for chunk in documents.chunks(Self::BIND_LIMIT / 7) {
             builder
                 .reset()
                 .push_values(chunk, |mut builder, document| {
                     builder
                         .push_bind(&document.id)
                         .push_bind(&document.original_sha256)
+                        .push_bind(&document.content_sha256)
                         .push_bind(document.preprocessing_step)
                         .push_bind(Json(&document.properties))
                         .push_bind(&document.tags)
@@ -133,6 +140,7 @@ impl Database {
                 .push(
                     " ON CONFLICT (document_id) DO UPDATE SET
                         original_sha256 = EXCLUDED.original_sha256,
+                        content_sha256 = EXCLUDED.content_sha256,
                         preprocessing_step = EXCLUDED.preprocessing_step,
                         properties = EXCLUDED.properties,
                         tags = EXCLUDED.tags,
@@ -316,13 +324,21 @@ impl Database {
         let mut builder = QueryBuilder::new(format!(
             "SELECT
                 s.document_id, s.sub_id, s.embedding {snippet},
-                d.tags {properties}
+                d.tags {properties},
+                CASE WHEN jsonb_typeof(d.properties -> '{BOOST_PROPERTY}') = 'number'
+                    THEN LEAST(GREATEST(
+                        (d.properties ->> '{BOOST_PROPERTY}')::numeric,
+                        {min_boost}), {max_boost}
+                    )::real
+                END AS boost
             FROM snippet s JOIN document d USING (document_id)
             WHERE d.is_candidate AND (s.document_id, s.sub_id) IN ",
             properties = include_properties
                 .then_some(", d.properties")
                 .unwrap_or_default(),
             snippet = include_snippet.then_some(", s.snippet").unwrap_or_default(),
+            min_boost = BOOST_BOUNDS.0,
+            max_boost = BOOST_BOUNDS.1,
         ));
         let mut chunks = IterAsTuple::chunks(
             Self::BIND_LIMIT / 2,
@@ -341,6 +357,7 @@ impl Database {
                         let sub_id = u32::from(row.try_get::<SqlBitCastU32, _>("sub_id")?);
                         let id = SnippetId::new(document_id, sub_id);
                         let tags = row.try_get("tags")?;
+                        let boost = row.try_get("boost")?;
                         let properties = if include_properties {
                             Some(row.try_get::<Json<_>, _>("properties")?.0)
                         } else {
@@ -362,6 +379,7 @@ impl Database {
                             properties,
                             snippet,
                             tags,
+                            boost,
                             dev: None,
                         })
                     })
@@ -784,6 +802,29 @@ impl Database {
         Ok(())
     }
 
+    async fn delete_cois(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &UserId,
+        ids: &[CoiId],
+    ) -> Result<(), Error> {
+        let mut builder = QueryBuilder::new("DELETE FROM center_of_interest WHERE coi_id IN ");
+        let mut chunks = IterAsTuple::chunks(Database::BIND_LIMIT - 1, ids);
+        while let Some(ids) = chunks.next() {
+            builder
+                .reset()
+                .push_tuple(ids)
+                .push(" AND user_id = ")
+                .push_bind(user_id)
+                .push(";")
+                .build()
+                .persistent(false)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     async fn upsert_interactions(
         tx: &mut Transaction<'_, Postgres>,
         user_id: &UserId,
@@ -1311,6 +1352,7 @@ impl storage::Interaction for Storage {
             .collect::<HashMap<_, _>>();
 
         let mut interests = Database::get_user_interests(&mut tx, user_id).await?;
+        let stored = interests.iter().map(|coi| coi.id).collect::<HashSet<_>>();
         let mut updates = HashMap::new();
         for document_id in interactions {
             if let Some(document) = snippet_map.get(&document_id) {
@@ -1328,6 +1370,12 @@ impl storage::Interaction for Storage {
             }
         }
 
+        // cois might have been evicted if the maximum number of cois was exceeded
+        let remaining = interests.iter().map(|coi| coi.id).collect::<HashSet<_>>();
+        updates.retain(|id, _| remaining.contains(id));
+        let evicted = stored.difference(&remaining).copied().collect_vec();
+
+        Database::delete_cois(&mut tx, user_id, &evicted).await?;
         Database::upsert_cois(&mut tx, user_id, time, &updates).await?;
         if store_user_history {
             Database::upsert_interactions(&mut tx, user_id, time, snippet_map.keys().copied())
@@ -1447,7 +1495,363 @@ impl storage::IndexedProperties for Storage {
     }
 }
 
+#[async_trait(?Send)]
+impl storage::PropertiesSchema for Storage {
+    async fn load(&self) -> Result<Option<PropertiesJsonSchema>, Error> {
+        let mut tx = self.postgres.begin().await?;
+        let schema = Database::load_properties_schema(&mut tx).await?;
+        tx.commit().await?;
+        Ok(schema)
+    }
+
+    async fn store(&self, schema: Option<&PropertiesJsonSchema>) -> Result<(), Error> {
+        let mut tx = self.postgres.begin().await?;
+        Database::store_properties_schema(&mut tx, schema).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl storage::ContentHash for Storage {
+    async fn get_ids(
+        &self,
+        hashes: &[Sha256Hash],
+    ) -> Result<HashMap<Sha256Hash, DocumentId>, Error> {
+        let mut tx = self.postgres.begin().await?;
+        let ids = Database::get_ids_by_content_sha256(&mut tx, hashes).await?;
+        tx.commit().await?;
+        Ok(ids)
+    }
+}
+
+#[async_trait(?Send)]
+impl storage::Reembedding for Storage {
+    async fn start(&self, model: &str, es_index_name: &str) -> Result<ReembeddingState, Error> {
+        let mut tx = self.postgres.begin().await?;
+        sqlx::query(
+            "INSERT INTO reembedding (model, es_index_name)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING;",
+        )
+        .bind(model)
+        .bind(es_index_name)
+        .execute(&mut *tx)
+        .await?;
+        let (model, es_index_name, last_document_id) =
+            sqlx::query_as::<_, (String, String, Option<DocumentId>)>(
+                "SELECT model, es_index_name, last_document_id
+                FROM reembedding;",
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(ReembeddingState {
+            model,
+            es_index_name,
+            last_document_id,
+        })
+    }
+
+    async fn get(
+        &self,
+        after: Option<&DocumentId>,
+        count: usize,
+    ) -> Result<Vec<DocumentForReembedding>, Error> {
+        let mut tx = self.postgres.begin().await?;
+        let documents = Database::get_documents_for_reembedding(&mut tx, after, count).await?;
+        tx.commit().await?;
+        Ok(documents)
+    }
+
+    async fn update(
+        &self,
+        state: &ReembeddingState,
+        documents: &[DocumentForReembedding],
+    ) -> Result<Warning<DocumentId>, Error> {
+        let Some(last_document) = documents.last() else {
+            return Ok(Warning::default());
+        };
+
+        let mut tx = self.postgres.begin().await?;
+        Database::insert_reembedded_snippets(&mut tx, documents).await?;
+        sqlx::query(
+            "UPDATE reembedding
+            SET last_document_id = $1;",
+        )
+        .bind(&last_document.id)
+        .execute(&mut *tx)
+        .await?;
+        let failed_documents = self
+            .elastic
+            .with_index(&state.es_index_name)
+            .insert_reembedded_documents(documents.iter().filter(|document| document.is_candidate))
+            .await?;
+        if failed_documents.is_empty() {
+            tx.commit().await?;
+        } else {
+            // the same batch is re-embedded on a retry, which also overwrites the documents which
+            // were inserted into the new index
+            tx.rollback().await?;
+        }
+        Ok(failed_documents)
+    }
+
+    async fn finish(&self) -> Result<(), Error> {
+        let mut tx = self.postgres.begin().await?;
+        sqlx::query(
+            "UPDATE snippet AS s
+            SET embedding = r.embedding
+            FROM reembedded_snippet AS r
+            WHERE s.document_id = r.document_id
+                AND s.sub_id = r.sub_id;",
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM reembedded_snippet;")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM reembedding;")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM center_of_interest;")
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn progress(&self, until: Option<&DocumentId>) -> Result<(usize, usize), Error> {
+        let (processed, total) = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT COUNT(*) FILTER (WHERE document_id <= $1), COUNT(*)
+            FROM document;",
+        )
+        .bind(until)
+        .fetch_one(&self.postgres)
+        .await?;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let progress = (processed as usize, total as usize);
+
+        Ok(progress)
+    }
+}
+
+impl Database {
+    async fn get_documents_for_reembedding(
+        tx: &mut Transaction<'_, Postgres>,
+        after: Option<&DocumentId>,
+        count: usize,
+    ) -> Result<Vec<DocumentForReembedding>, Error> {
+        #[allow(clippy::cast_possible_wrap)]
+        let documents = sqlx::query_as::<
+            _,
+            (
+                DocumentId,
+                PreprocessingStep,
+                Json<DocumentProperties>,
+                DocumentTags,
+                bool,
+            ),
+        >(
+            "SELECT document_id, preprocessing_step, properties, tags, is_candidate
+            FROM document
+            WHERE $1::TEXT IS NULL OR document_id > $1
+            ORDER BY document_id
+            LIMIT $2;",
+        )
+        .bind(after)
+        .bind(count as i64)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut snippets = HashMap::<_, Vec<_>>::new();
+        {
+            let mut builder = QueryBuilder::new(
+                "SELECT document_id, sub_id, snippet, embedding
+                FROM snippet
+                WHERE document_id IN ",
+            );
+            let mut chunks = IterAsTuple::chunks(
+                Self::BIND_LIMIT,
+                documents
+                    .iter()
+                    .map(|(document_id, _, _, _, _)| document_id),
+            );
+            while let Some(ids) = chunks.next() {
+                builder
+                    .reset()
+                    .push_tuple(ids)
+                    .build_query_as::<SqlSnippet>()
+                    .fetch(&mut *tx)
+                    .try_for_each(|snippet| {
+                        snippets.entry(snippet.document_id).or_default().push((
+                            u32::from(snippet.sub_id),
+                            DocumentContent {
+                                snippet: snippet.snippet,
+                                embedding: snippet.embedding,
+                            },
+                        ));
+                        future::ok(())
+                    })
+                    .await?;
+            }
+        }
+
+        Ok(documents
+            .into_iter()
+            .map(|(id, preprocessing_step, properties, tags, is_candidate)| {
+                //Hint: We currently assume there are no gaps.
+                //      I.e. if there are 10 snippets their sub ids are 0..10.
+                let snippets = snippets
+                    .remove(&id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .sorted_by_key(|(sub_id, _)| *sub_id)
+                    .map(|(_, content)| content)
+                    .collect();
+
+                DocumentForReembedding {
+                    id,
+                    snippets,
+                    preprocessing_step,
+                    properties: properties.0,
+                    tags,
+                    is_candidate,
+                }
+            })
+            .collect())
+    }
+
+    async fn insert_reembedded_snippets(
+        tx: &mut Transaction<'_, Postgres>,
+        documents: &[DocumentForReembedding],
+    ) -> Result<(), Error> {
+        let mut snippets = Chunks::new(
+            Self::BIND_LIMIT / 4,
+            documents.iter().flat_map(|document| {
+                document.snippets.iter().enumerate().map(
+                    |(sub_id, DocumentContent { snippet, embedding })| {
+                        (
+                            &document.id,
+                            #[allow(clippy::cast_possible_truncation)]
+                            SqlBitCastU32::from(sub_id as u32),
+                            snippet,
+                            embedding,
+                        )
+                    },
+                )
+            }),
+        );
+
+        // snippets which have changed since they were loaded are skipped
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO reembedded_snippet (document_id, sub_id, embedding)
+            SELECT s.document_id, s.sub_id, u.embedding
+            FROM snippet AS s, (",
+        );
+        while let Some(chunk) = snippets.next() {
+            builder
+                .reset()
+                .push_values(
+                    chunk,
+                    |mut builder, (document_id, sub_id, snippet, embedding)| {
+                        builder
+                            .push_bind(document_id)
+                            .push_bind(sub_id)
+                            .push_bind(snippet)
+                            .push_bind(embedding);
+                    },
+                )
+                .push(
+                    ") AS u (document_id, sub_id, snippet, embedding)
+                    WHERE s.document_id = u.document_id
+                        AND s.sub_id = u.sub_id
+                        AND s.snippet = u.snippet
+                    ON CONFLICT (document_id, sub_id) DO UPDATE
+                    SET embedding = EXCLUDED.embedding;",
+                )
+                .build()
+                .persistent(false)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Database {
+    async fn get_ids_by_content_sha256(
+        tx: &mut Transaction<'_, Postgres>,
+        hashes: &[Sha256Hash],
+    ) -> Result<HashMap<Sha256Hash, DocumentId>, Error> {
+        let mut builder = QueryBuilder::new(
+            "SELECT content_sha256, document_id
+            FROM document
+            WHERE content_sha256 IN ",
+        );
+        let mut ids = HashMap::with_capacity(hashes.len());
+        let mut chunks = IterAsTuple::chunks(Self::BIND_LIMIT, hashes.iter());
+        while let Some(hashes) = chunks.next() {
+            let chunk = builder
+                .reset()
+                .push_tuple(hashes)
+                .push(" ORDER BY document_id;")
+                .build()
+                .persistent(false)
+                .try_map(|row: PgRow| {
+                    Ok((
+                        row.try_get::<Sha256Hash, _>("content_sha256")?,
+                        row.try_get::<DocumentId, _>("document_id")?,
+                    ))
+                })
+                .fetch_all(&mut *tx)
+                .await?;
+            for (hash, id) in chunk {
+                ids.entry(hash).or_insert(id);
+            }
+        }
+
+        Ok(ids)
+    }
+
+    async fn load_properties_schema(
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> Result<Option<PropertiesJsonSchema>, Error> {
+        let schema = sqlx::query_as::<_, (Json<PropertiesJsonSchema>,)>(
+            "SELECT schema FROM properties_schema;",
+        )
+        .fetch_optional(tx)
+        .await?
+        .map(|(schema,)| schema.0);
+
+        Ok(schema)
+    }
+
+    async fn store_properties_schema(
+        tx: &mut Transaction<'_, Postgres>,
+        schema: Option<&PropertiesJsonSchema>,
+    ) -> Result<(), Error> {
+        if let Some(schema) = schema {
+            sqlx::query(
+                "INSERT INTO properties_schema (schema)
+                VALUES ($1)
+                ON CONFLICT (singleton) DO UPDATE
+                SET schema = EXCLUDED.schema;",
+            )
+            .bind(Json(schema))
+            .execute(tx)
+            .await?;
+        } else {
+            sqlx::query("DELETE FROM properties_schema;")
+                .execute(tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     async fn load_schema(
         tx: &mut Transaction<'_, Postgres>,
     ) -> Result<IndexedPropertiesSchema, Error> {
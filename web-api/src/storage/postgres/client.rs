@@ -16,7 +16,7 @@ use async_stream::try_stream;
 use either::Either;
 use futures_util::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt, TryStreamExt};
 use sqlx::{
-    pool::{PoolConnection, PoolOptions},
+    pool::PoolConnection,
     postgres::{PgQueryResult, PgRow, PgStatement, PgTypeInfo},
     Acquire,
     Describe,
@@ -81,9 +81,8 @@ impl Database {
     ) -> Result<DatabaseBuilder, SetupError> {
         let options = config.to_connection_options()?;
         info!("starting postgres setup");
-        let pool = PoolOptions::new()
-            .min_connections(u32::from(config.min_pool_size))
-            .max_connections(u32::from(config.max_pool_size))
+        let pool = config
+            .to_pool_options()
             .after_release(|conn, _metadata| {
                 async {
                     sqlx::query("RESET ROLE;").execute(conn).await?;
@@ -0,0 +1,172 @@
+// Copyright 2023 Xayn AG
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! This module contains the optional per tenant schema of the document properties.
+
+use std::collections::HashMap;
+
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::common::PropertiesSchemaViolation,
+    models::{DocumentProperties, DocumentPropertyId},
+};
+
+/// A subset of a JSON schema for the properties of a document.
+///
+/// Each document property can be restricted to a type, properties can be required and
+/// properties which aren't part of the schema can be disallowed.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PropertiesJsonSchema {
+    #[serde(default)]
+    properties: HashMap<DocumentPropertyId, PropertyJsonSchema>,
+    #[serde(default)]
+    required: Vec<DocumentPropertyId>,
+    #[serde(
+        rename = "additionalProperties",
+        default = "additional_properties_default"
+    )]
+    additional_properties: bool,
+}
+
+fn additional_properties_default() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct PropertyJsonSchema {
+    r#type: PropertyJsonType,
+}
+
+#[derive(Debug, Clone, Copy, Display, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PropertyJsonType {
+    #[display(fmt = "null")]
+    Null,
+    #[display(fmt = "boolean")]
+    Boolean,
+    #[display(fmt = "number")]
+    Number,
+    #[display(fmt = "string")]
+    String,
+    #[display(fmt = "array")]
+    Array,
+}
+
+impl PropertyJsonType {
+    fn matches(self, value: &Value) -> bool {
+        matches!(
+            (self, value),
+            (Self::Null, Value::Null)
+                | (Self::Boolean, Value::Bool(_))
+                | (Self::Number, Value::Number(_))
+                | (Self::String, Value::String(_))
+                | (Self::Array, Value::Array(_))
+        )
+    }
+}
+
+impl PropertiesJsonSchema {
+    /// Validates the properties against the schema and reports all violations.
+    pub(crate) fn validate(
+        &self,
+        properties: &DocumentProperties,
+    ) -> Result<(), PropertiesSchemaViolation> {
+        let mut violations = self
+            .required
+            .iter()
+            .filter(|property_id| !properties.contains_key(*property_id))
+            .map(|property_id| format!("missing required property {property_id}"))
+            .collect::<Vec<_>>();
+
+        for (property_id, value) in properties.iter() {
+            match self.properties.get(property_id) {
+                Some(schema) if !schema.r#type.matches(value) => violations.push(format!(
+                    "property {property_id} must be of type {}",
+                    schema.r#type,
+                )),
+                None if !self.additional_properties => {
+                    violations.push(format!("property {property_id} is not allowed"));
+                }
+                _ => {}
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            violations.sort_unstable();
+            Err(PropertiesSchemaViolation { violations })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::models::DocumentProperty;
+
+    fn properties(properties: Value) -> DocumentProperties {
+        let properties =
+            serde_json::from_value::<HashMap<DocumentPropertyId, DocumentProperty>>(properties)
+                .unwrap();
+        DocumentProperties::new(properties, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let schema = serde_json::from_value::<PropertiesJsonSchema>(json!({
+            "properties": {
+                "category": { "type": "string" },
+                "rank": { "type": "number" }
+            },
+            "required": ["category"],
+            "additionalProperties": false
+        }))
+        .unwrap();
+
+        assert!(schema
+            .validate(&properties(json!({ "category": "news", "rank": 3 })))
+            .is_ok());
+        assert_eq!(
+            schema
+                .validate(&properties(json!({ "categroy": "news", "rank": "3" })))
+                .unwrap_err()
+                .violations,
+            [
+                "missing required property category",
+                "property categroy is not allowed",
+                "property rank must be of type number",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_validate_additional_properties() {
+        let schema = serde_json::from_value::<PropertiesJsonSchema>(json!({
+            "properties": { "category": { "type": "string" } }
+        }))
+        .unwrap();
+
+        assert!(schema
+            .validate(&properties(json!({ "other": true })))
+            .is_ok());
+    }
+}